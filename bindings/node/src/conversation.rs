@@ -0,0 +1,109 @@
+use crate::{run, Run};
+use anyhow::Context;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::conversation;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// A model for holding conversations.
+#[napi]
+pub struct ConversationModel {
+    model: Arc<Mutex<conversation::ConversationModel>>,
+}
+
+#[napi]
+impl ConversationModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<ConversationModel>> {
+        run(|| {
+            let model = conversation::ConversationModel::new(Default::default())
+                .context("Failed to create conversation model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    #[napi]
+    pub fn create_conversation_manager(&self) -> ConversationManager {
+        ConversationManager {
+            model: self.model.clone(),
+            manager: Arc::new(Mutex::new(conversation::ConversationManager::new())),
+        }
+    }
+}
+
+/// Manages the conversations of a conversation model.
+#[napi]
+pub struct ConversationManager {
+    model: Arc<Mutex<conversation::ConversationModel>>,
+    manager: Arc<Mutex<conversation::ConversationManager>>,
+}
+
+#[napi(object)]
+pub struct ConversationInit {
+    /// Whether `Conversation.history` is updated for every message and response.
+    pub include_history: Option<bool>,
+}
+
+#[napi]
+impl ConversationManager {
+    #[napi]
+    pub fn create_conversation(&self, init: Option<ConversationInit>) -> Conversation {
+        let id = self.manager.lock().unwrap().create_empty();
+        Conversation {
+            model: self.model.clone(),
+            manager: self.manager.clone(),
+            id,
+            include_history: init.and_then(|init| init.include_history).unwrap_or(false),
+            history: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+/// A conversation and its history.
+#[napi]
+pub struct Conversation {
+    model: Arc<Mutex<conversation::ConversationModel>>,
+    manager: Arc<Mutex<conversation::ConversationManager>>,
+    id: Uuid,
+    include_history: bool,
+    history: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+#[napi]
+impl Conversation {
+    /// The messages that were sent and the responses to them.
+    #[napi(getter, ts_return_type = "[string, string][]")]
+    pub fn history(&self) -> Vec<Vec<String>> {
+        let history = self.history.lock().unwrap();
+        history
+            .iter()
+            .map(|(message, response)| vec![message.clone(), response.clone()])
+            .collect()
+    }
+
+    /// Sends the message to the model and resolves with its response.
+    #[napi]
+    pub fn send_message(&self, message: String) -> AsyncTask<Run<String>> {
+        let (model, manager, history) = (
+            self.model.clone(),
+            self.manager.clone(),
+            self.history.clone(),
+        );
+        let (id, include_history) = (self.id, self.include_history);
+        run(move || {
+            let response = bertml::send_message(
+                &model.lock().unwrap(),
+                &mut manager.lock().unwrap(),
+                &id,
+                &message,
+            )?;
+            if include_history {
+                history.lock().unwrap().push((message, response.clone()));
+            }
+            Ok(response)
+        })
+    }
+}
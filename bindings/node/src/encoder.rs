@@ -0,0 +1,74 @@
+use crate::{from_js, from_js_or_default, run, tokenizer, Run, Tensor};
+use bertml::{BertModelInit, EncoderInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// The outputs of the encoder. The inputs are padded to the same number of tokens, so the rows
+/// after `tokenIds[i].length` of input `i` are padding.
+#[napi(object)]
+pub struct EncoderOutput {
+    /// `[batch, tokens, hidden]`
+    pub last_hidden_state: Tensor,
+    /// `[batch, hidden]`
+    pub pooled_output: Option<Tensor>,
+    /// `[layers, batch, tokens, hidden]`, when `allLayers` is set.
+    pub hidden_states: Option<Tensor>,
+    /// `[layers, batch, heads, tokens, tokens]`, when `attentions` is set.
+    pub attentions: Option<Tensor>,
+    /// The token ids of each input.
+    pub token_ids: Vec<Vec<i64>>,
+}
+
+/// A BERT encoder without a task head, for reading its hidden states and attentions.
+#[napi]
+pub struct EncoderModel {
+    model: Arc<Mutex<bertml::EncoderModel>>,
+}
+
+#[napi]
+impl EncoderModel {
+    #[napi]
+    pub fn create(init: Option<Value>) -> Result<AsyncTask<Run<EncoderModel>>> {
+        let init: BertModelInit = from_js_or_default(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(bertml::EncoderModel::new(&init)?)),
+            })
+        }))
+    }
+
+    /// Runs the encoder on the `{inputs, allLayers, attentions}` as one batch.
+    #[napi]
+    pub fn forward(&self, init: Value) -> Result<AsyncTask<Run<EncoderOutput>>> {
+        let init: EncoderInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run(move || {
+            let output = model.lock().unwrap().forward(&init)?;
+            Ok(EncoderOutput {
+                last_hidden_state: Tensor::new(&output.last_hidden_state),
+                pooled_output: output.pooled_output.as_ref().map(Tensor::new),
+                hidden_states: output.hidden_states.as_ref().map(Tensor::new),
+                attentions: output.attentions.as_ref().map(Tensor::new),
+                token_ids: output.token_ids,
+            })
+        }))
+    }
+
+    #[napi]
+    pub fn tokenize(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        tokenizer::tokenize(&self.model, init)
+    }
+
+    #[napi]
+    pub fn decode(&self, init: Value) -> Result<AsyncTask<Run<Vec<String>>>> {
+        tokenizer::decode(&self.model, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    #[napi]
+    pub fn max_length(&self) -> u32 {
+        tokenizer::max_length(&self.model)
+    }
+}
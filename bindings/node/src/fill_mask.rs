@@ -0,0 +1,54 @@
+use crate::{from_js, from_js_or_default, run, run_json, tokenizer, Run};
+use bertml::{BertModelInit, FillMaskInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A BERT masked language model that predicts the most probable tokens for masks.
+#[napi]
+pub struct FillMaskModel {
+    model: Arc<Mutex<bertml::FillMaskModel>>,
+}
+
+#[napi]
+impl FillMaskModel {
+    #[napi]
+    pub fn create(init: Option<Value>) -> Result<AsyncTask<Run<FillMaskModel>>> {
+        let init: BertModelInit = from_js_or_default(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(bertml::FillMaskModel::new(&init)?)),
+            })
+        }))
+    }
+
+    /// Gets the `topK` candidates for each mask of each input.
+    #[napi]
+    pub fn predict(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: FillMaskInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            model
+                .lock()
+                .unwrap()
+                .candidates(&init.inputs, init.mask_token(), init.top_k)
+        }))
+    }
+
+    #[napi]
+    pub fn tokenize(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        tokenizer::tokenize(&self.model, init)
+    }
+
+    #[napi]
+    pub fn decode(&self, init: Value) -> Result<AsyncTask<Run<Vec<String>>>> {
+        tokenizer::decode(&self.model, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    #[napi]
+    pub fn max_length(&self) -> u32 {
+        tokenizer::max_length(&self.model)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{from_js_or_default, run, run_json, Run};
+use bertml::KeywordExtractionModelInit;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::keywords_extraction;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for extracting the keyphrases of inputs.
+#[napi]
+pub struct KeywordExtractionModel {
+    model: Arc<Mutex<keywords_extraction::KeywordExtractionModel<'static>>>,
+}
+
+#[napi]
+impl KeywordExtractionModel {
+    #[napi]
+    pub fn create(init: Option<Value>) -> Result<AsyncTask<Run<KeywordExtractionModel>>> {
+        let init: KeywordExtractionModelInit = from_js_or_default(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(init.create()?)),
+            })
+        }))
+    }
+
+    /// Extracts the keyphrases of each of the inputs.
+    #[napi]
+    pub fn extract(&self, inputs: Vec<String>) -> AsyncTask<Run<Value>> {
+        let model = self.model.clone();
+        run_json(move || bertml::extract_keywords(&model.lock().unwrap(), &inputs))
+    }
+}
@@ -0,0 +1,110 @@
+//! Node.js bindings to the typed API of bertml.
+//!
+//! The configs and inputs are the same objects that the Deno bindings take and the results have
+//! the same shapes, so both go through the same preprocessing, model loading and output
+//! conversion in `bertml`. Every method that runs a model returns a `Promise` and runs on the
+//! libuv thread pool, so the event loop keeps running during inference.
+
+mod conversation;
+mod encoder;
+mod fill_mask;
+mod keyword_extraction;
+mod ner;
+mod pos_tagging;
+mod qa;
+mod sentence_embeddings;
+mod sentiment;
+mod summarization;
+mod text_generation;
+mod tokenizer;
+mod translation;
+mod vector_index;
+mod zero_shot_classification;
+
+use napi::bindgen_prelude::*;
+use napi::{Env, Task};
+use napi_derive::napi;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+fn to_napi_err(e: anyhow::Error) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+/// A task that runs `f` on the libuv thread pool and resolves its promise with the result.
+pub struct Run<T> {
+    f: Option<Box<dyn FnOnce() -> anyhow::Result<T> + Send>>,
+}
+
+impl<T> Task for Run<T>
+where
+    T: ToNapiValue + TypeName + Send + 'static,
+{
+    type Output = T;
+    type JsValue = T;
+
+    fn compute(&mut self) -> Result<T> {
+        let f = self.f.take().expect("Tasks are only computed once.");
+        f().map_err(to_napi_err)
+    }
+
+    fn resolve(&mut self, _env: Env, output: T) -> Result<T> {
+        Ok(output)
+    }
+}
+
+/// Runs `f` on the libuv thread pool.
+fn run<T, F>(f: F) -> AsyncTask<Run<T>>
+where
+    T: ToNapiValue + TypeName + Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    AsyncTask::new(Run {
+        f: Some(Box::new(f)),
+    })
+}
+
+/// Same as `run`, where the result is one of the result structs of bertml that is converted to
+/// a JavaScript value like the JSON results of the Deno bindings.
+fn run_json<T, F>(f: F) -> AsyncTask<Run<Value>>
+where
+    T: Serialize,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    run(move || Ok(serde_json::to_value(f()?)?))
+}
+
+/// Converts a JavaScript value into one of the config or input structs of bertml.
+fn from_js<T: DeserializeOwned>(value: Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Same as `from_js`, where `undefined` is converted like an empty object.
+fn from_js_or_default<T: DeserializeOwned>(value: Option<Value>) -> Result<T> {
+    from_js(value.unwrap_or_else(|| Value::Object(Default::default())))
+}
+
+/// A tensor copied from the model, with the values in row-major order.
+#[napi(object)]
+pub struct Tensor {
+    pub shape: Vec<u32>,
+    pub data: Float32Array,
+}
+
+impl Tensor {
+    /// Copies the tensor to the CPU as `f32` values.
+    fn new(tensor: &tch::Tensor) -> Self {
+        let tensor = tensor
+            .to_kind(tch::Kind::Float)
+            .to_device(tch::Device::Cpu)
+            .contiguous();
+        let numel = tensor.numel();
+        let mut values = vec![0f32; numel];
+        tensor.copy_data(&mut values, numel);
+        Self {
+            shape: tensor.size().into_iter().map(|d| d as u32).collect(),
+            data: Float32Array::new(values),
+        }
+    }
+}
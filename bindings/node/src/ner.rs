@@ -0,0 +1,34 @@
+use crate::{run, run_json, Run};
+use anyhow::Context;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::ner;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for finding the named entities in inputs.
+#[napi]
+pub struct NERModel {
+    model: Arc<Mutex<ner::NERModel>>,
+}
+
+#[napi]
+impl NERModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<NERModel>> {
+        run(|| {
+            let model =
+                ner::NERModel::new(Default::default()).context("Failed to create NER model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Predicts the entities of each of the inputs.
+    #[napi]
+    pub fn predict(&self, inputs: Vec<String>) -> AsyncTask<Run<Value>> {
+        let model = self.model.clone();
+        run_json(move || Ok(bertml::predict_entities(&model.lock().unwrap(), &inputs)))
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{from_js, run, run_json, Run};
+use anyhow::Context;
+use bertml::{POSPredictInit, TagSet};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::pos_tagging;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for finding the part of speech of the words in inputs.
+#[napi]
+pub struct POSModel {
+    pub(crate) model: Arc<Mutex<pos_tagging::POSModel>>,
+}
+
+#[napi]
+impl POSModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<POSModel>> {
+        run(|| {
+            let model = pos_tagging::POSModel::new(Default::default())
+                .context("Failed to load Parts of Speech Tagging model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Predicts the parts of speech of the words in each of the inputs, with the labels of the
+    /// `tagSet` of the options (`"ptb"` or `"ud"`).
+    #[napi]
+    pub fn predict(
+        &self,
+        inputs: Vec<String>,
+        options: Option<Value>,
+    ) -> Result<AsyncTask<Run<Value>>> {
+        let tag_set = match options.as_ref().and_then(|options| options.get("tagSet")) {
+            Some(tag_set) => from_js::<TagSet>(tag_set.clone())?,
+            None => TagSet::default(),
+        };
+        let init = POSPredictInit { inputs, tag_set };
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            Ok(bertml::predict_tags(&model.lock().unwrap(), &init))
+        }))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{from_js, from_js_or_default, run, run_json, Run};
+use bertml::{
+    JSPassage, JSQaContextInput, JSQaInput, PassageCorpus, QAModelInit, QaCorpusQueryInit,
+};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::question_answering::QaInput;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// The `QAQueryInit` of `models/qa.ts`, where the options default like they do there.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QAQueryInit {
+    question_groups: Vec<JSQaInput>,
+    n_top_answers: Option<usize>,
+    batch_size: Option<usize>,
+    no_answer_threshold: Option<f64>,
+}
+
+/// The `QAContextQueryInit` of `models/qa.ts`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QAContextQueryInit {
+    contexts: Vec<JSQaContextInput>,
+    n_top_answers: Option<usize>,
+    batch_size: Option<usize>,
+    no_answer_threshold: Option<f64>,
+}
+
+/// The `QACorpusQueryInit` of `models/qa.ts`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QACorpusQueryInit {
+    questions: Vec<String>,
+    n_passages: Option<usize>,
+    n_top_answers: Option<usize>,
+    batch_size: Option<usize>,
+    no_answer_threshold: Option<f64>,
+}
+
+/// A model used for finding the answer within a context for inputs.
+#[napi]
+pub struct QAModel {
+    model: Arc<Mutex<bertml::QAModel>>,
+}
+
+#[napi]
+impl QAModel {
+    #[napi]
+    pub fn create(init: Option<Value>) -> Result<AsyncTask<Run<QAModel>>> {
+        let init: QAModelInit = from_js_or_default(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(init.create()?)),
+            })
+        }))
+    }
+
+    /// Answers each `{question, context}` of the question groups with the n top answers.
+    #[napi]
+    pub fn query(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: QAQueryInit = from_js(init)?;
+        let inputs = init
+            .question_groups
+            .into_iter()
+            .map(QaInput::from)
+            .collect::<Vec<_>>();
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            Ok(bertml::predict_answers(
+                &model.lock().unwrap(),
+                inputs,
+                init.n_top_answers.unwrap_or(1),
+                init.batch_size.unwrap_or(32),
+                init.no_answer_threshold,
+            ))
+        }))
+    }
+
+    /// Same as `query` except that each `{context, questions}` gives a context once for all of
+    /// its questions. The answers are grouped by context and then by question.
+    #[napi]
+    pub fn query_contexts(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: QAContextQueryInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            Ok(bertml::predict_context_answers(
+                &model.lock().unwrap(),
+                init.contexts,
+                init.n_top_answers.unwrap_or(1),
+                init.batch_size.unwrap_or(32),
+                init.no_answer_threshold,
+            ))
+        }))
+    }
+
+    /// Indexes the `{id, text}` passages to ask questions about.
+    #[napi]
+    pub fn create_corpus(&self, passages: Value) -> Result<AsyncTask<Run<QACorpus>>> {
+        let passages: Vec<JSPassage> = from_js(passages)?;
+        let model = self.model.clone();
+        Ok(run(move || {
+            Ok(QACorpus {
+                model,
+                corpus: Arc::new(PassageCorpus::new(passages)),
+            })
+        }))
+    }
+}
+
+/// Passages indexed for BM25 retrieval that the questions are answered from.
+#[napi]
+pub struct QACorpus {
+    model: Arc<Mutex<bertml::QAModel>>,
+    corpus: Arc<PassageCorpus>,
+}
+
+#[napi]
+impl QACorpus {
+    /// Retrieves the passages that match each question the best with BM25 and returns the top
+    /// answers found in those passages.
+    #[napi]
+    pub fn query(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: QACorpusQueryInit = from_js(init)?;
+        let init = QaCorpusQueryInit {
+            questions: init.questions,
+            n_passages: init.n_passages.unwrap_or(3),
+            n_top_answers: init.n_top_answers.unwrap_or(1),
+            batch_size: init.batch_size.unwrap_or(32),
+            no_answer_threshold: init.no_answer_threshold,
+        };
+        let (model, corpus) = (self.model.clone(), self.corpus.clone());
+        Ok(run_json(move || {
+            Ok(corpus.query(&model.lock().unwrap(), &init))
+        }))
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{from_js, from_js_or_default, run, Run};
+use bertml::{SentenceEmbeddingsInit, SentenceEmbeddingsModelInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::sentence_embeddings;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for encoding sentences into vectors.
+#[napi]
+pub struct SentenceEmbeddingsModel {
+    pub(crate) model: Arc<Mutex<sentence_embeddings::SentenceEmbeddingsModel>>,
+}
+
+#[napi]
+impl SentenceEmbeddingsModel {
+    #[napi]
+    pub fn create(init: Option<Value>) -> Result<AsyncTask<Run<SentenceEmbeddingsModel>>> {
+        let init: SentenceEmbeddingsModelInit = from_js_or_default(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(init.create()?)),
+            })
+        }))
+    }
+
+    /// Encodes the `{inputs, normalize}` into one vector per input.
+    #[napi]
+    pub fn encode(&self, init: Value) -> Result<AsyncTask<Run<Vec<Float32Array>>>> {
+        let init: SentenceEmbeddingsInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run(move || {
+            let embeddings =
+                bertml::embed_sentences(&model.lock().unwrap(), &init.inputs, init.normalize)?;
+            Ok(embeddings.into_iter().map(Float32Array::new).collect())
+        }))
+    }
+}
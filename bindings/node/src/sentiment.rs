@@ -0,0 +1,109 @@
+use crate::pos_tagging::POSModel;
+use crate::{run, run_json, Run};
+use anyhow::Context;
+use bertml::AspectClassifier;
+use napi::bindgen_prelude::*;
+use napi::{Env, JsObject, NapiRaw};
+use napi_derive::napi;
+use rust_bert::pipelines::{pos_tagging, sentiment};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// The `{inputs, aspects, posModel, maxLength}` of `predictAspects`, which is read by hand since
+/// the POS model is a class instance rather than JSON.
+pub(crate) struct AspectSentimentInit {
+    pub inputs: Vec<String>,
+    aspects: Option<Vec<String>>,
+    pos_model: Option<Arc<Mutex<pos_tagging::POSModel>>>,
+    pub max_length: usize,
+}
+
+impl AspectSentimentInit {
+    pub(crate) fn from_js(env: Env, init: JsObject) -> Result<Self> {
+        let pos_model = match init.get::<_, JsObject>("posModel")? {
+            Some(pos_model) => {
+                let pos_model = unsafe { POSModel::from_napi_ref(env.raw(), pos_model.raw())? };
+                Some(pos_model.model.clone())
+            }
+            None => None,
+        };
+        Ok(Self {
+            inputs: init.get("inputs")?.unwrap_or_default(),
+            aspects: init.get("aspects")?,
+            pos_model,
+            max_length: init.get::<_, u32>("maxLength")?.unwrap_or(128) as usize,
+        })
+    }
+
+    /// Gets the aspects of each input like `aspect_sentiment_predict`, either the given aspects
+    /// for every input or the noun phrases that the POS model finds.
+    pub(crate) fn resolve_aspects(&self) -> anyhow::Result<Vec<Vec<String>>> {
+        match (&self.aspects, &self.pos_model) {
+            (Some(aspects), _) => Ok(vec![aspects.clone(); self.inputs.len()]),
+            (None, Some(pos_model)) => Ok(bertml::extract_noun_phrases(
+                &pos_model.lock().unwrap(),
+                &self.inputs,
+            )),
+            (None, None) => Err(anyhow::anyhow!(
+                "Expected either aspects or a POS model to extract aspects with."
+            )),
+        }
+    }
+}
+
+/// A model used to calculate the sentiment of inputs.
+#[napi]
+pub struct SentimentModel {
+    model: Arc<Mutex<sentiment::SentimentModel>>,
+}
+
+#[napi]
+impl SentimentModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<SentimentModel>> {
+        run(|| {
+            let model = sentiment::SentimentModel::new(Default::default())
+                .context("Failed to load sentiment model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Predicts the sentiments of the inputs.
+    #[napi]
+    pub fn predict(&self, inputs: Vec<String>) -> AsyncTask<Run<Value>> {
+        let model = self.model.clone();
+        run_json(move || Ok(bertml::predict_sentiments(&model.lock().unwrap(), &inputs)))
+    }
+
+    /// Splits each of the documents into sentences and predicts the sentiment of each sentence
+    /// along with aggregate statistics for the whole document.
+    #[napi]
+    pub fn predict_documents(&self, documents: Vec<String>) -> AsyncTask<Run<Value>> {
+        let model = self.model.clone();
+        run_json(move || {
+            Ok(bertml::predict_documents(
+                &model.lock().unwrap(),
+                &documents,
+            ))
+        })
+    }
+
+    /// Predicts the sentiment towards each aspect mentioned in the inputs.
+    #[napi(ts_args_type = "init: { inputs: string[], aspects?: string[], posModel?: POSModel }")]
+    pub fn predict_aspects(&self, env: Env, init: JsObject) -> Result<AsyncTask<Run<Value>>> {
+        let init = AspectSentimentInit::from_js(env, init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            let aspects = init.resolve_aspects()?;
+            let model = model.lock().unwrap();
+            Ok(bertml::predict_aspects(
+                AspectClassifier::Sentiment(&model),
+                &init.inputs,
+                &aspects,
+                128,
+            ))
+        }))
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{run, Run};
+use anyhow::Context;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::summarization;
+use std::sync::{Arc, Mutex};
+
+/// A model for summarizing inputs.
+#[napi]
+pub struct SummarizationModel {
+    model: Arc<Mutex<summarization::SummarizationModel>>,
+}
+
+#[napi]
+impl SummarizationModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<SummarizationModel>> {
+        run(|| {
+            let model = summarization::SummarizationModel::new(Default::default())
+                .context("Failed to create summarization model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Summarizes each of the inputs.
+    #[napi]
+    pub fn summarize(&self, inputs: Vec<String>) -> AsyncTask<Run<Vec<String>>> {
+        let model = self.model.clone();
+        run(move || Ok(bertml::summarize(&model.lock().unwrap(), &inputs)))
+    }
+}
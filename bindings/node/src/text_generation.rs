@@ -0,0 +1,38 @@
+use crate::{from_js, run, Run};
+use anyhow::Context;
+use bertml::TextGenerationInit;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::text_generation;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for generating continuations of inputs.
+#[napi]
+pub struct TextGenerationModel {
+    model: Arc<Mutex<text_generation::TextGenerationModel>>,
+}
+
+#[napi]
+impl TextGenerationModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<TextGenerationModel>> {
+        run(|| {
+            let model = text_generation::TextGenerationModel::new(Default::default())
+                .context("Failed to create text generation model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Generates a continuation of each of the `{inputs, prefix}`.
+    #[napi]
+    pub fn generate(&self, init: Value) -> Result<AsyncTask<Run<Vec<String>>>> {
+        let init: TextGenerationInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run(move || {
+            Ok(bertml::generate(&model.lock().unwrap(), &init))
+        }))
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{from_js, run, run_json, Run};
+use bertml::{DecodeInit, TokenizeInit, TokenizerInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::common::TokenizerOption;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// The models that expose their tokenizer, like `with_tokenizer` of the FFI.
+pub(crate) trait WithTokenizer: Send + 'static {
+    fn tokenizer(&self) -> &TokenizerOption;
+    fn max_length(&self) -> usize;
+}
+
+impl WithTokenizer for bertml::StandaloneTokenizer {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+impl WithTokenizer for bertml::FillMaskModel {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+impl WithTokenizer for bertml::EncoderModel {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+/// Tokenizes the `{inputs, addSpecialTokens, maxLength}` with the tokenizer of the model.
+pub(crate) fn tokenize<T: WithTokenizer>(
+    model: &Arc<Mutex<T>>,
+    init: Value,
+) -> Result<AsyncTask<Run<Value>>> {
+    let init: TokenizeInit = from_js(init)?;
+    let model = model.clone();
+    Ok(run_json(move || {
+        let model = model.lock().unwrap();
+        Ok(bertml::tokenize_batch(
+            model.tokenizer(),
+            &init,
+            model.max_length(),
+        ))
+    }))
+}
+
+/// Decodes the `{ids, skipSpecialTokens, cleanUpTokenizationSpaces}` with the tokenizer of the
+/// model.
+pub(crate) fn decode<T: WithTokenizer>(
+    model: &Arc<Mutex<T>>,
+    init: Value,
+) -> Result<AsyncTask<Run<Vec<String>>>> {
+    let init: DecodeInit = from_js(init)?;
+    let model = model.clone();
+    Ok(run(move || {
+        Ok(bertml::decode_batch(
+            model.lock().unwrap().tokenizer(),
+            &init,
+        ))
+    }))
+}
+
+/// The maximum number of tokens of an input to the model.
+pub(crate) fn max_length<T: WithTokenizer>(model: &Arc<Mutex<T>>) -> u32 {
+    model.lock().unwrap().max_length() as u32
+}
+
+/// A tokenizer that is loaded from vocab files without a model.
+#[napi]
+pub struct Tokenizer {
+    tokenizer: Arc<Mutex<bertml::StandaloneTokenizer>>,
+}
+
+#[napi]
+impl Tokenizer {
+    #[napi]
+    pub fn create(init: Value) -> Result<AsyncTask<Run<Tokenizer>>> {
+        let init: TokenizerInit = from_js(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                tokenizer: Arc::new(Mutex::new(init.create()?)),
+            })
+        }))
+    }
+
+    #[napi]
+    pub fn tokenize(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        tokenize(&self.tokenizer, init)
+    }
+
+    #[napi]
+    pub fn decode(&self, init: Value) -> Result<AsyncTask<Run<Vec<String>>>> {
+        decode(&self.tokenizer, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    #[napi]
+    pub fn max_length(&self) -> u32 {
+        max_length(&self.tokenizer)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{from_js, run, Run};
+use bertml::{TranslationInit, TranslationModelInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rust_bert::pipelines::translation;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for translating text from any of its source languages to any of its target
+/// languages, where the languages are the indices of `Language` in the Deno bindings.
+#[napi]
+pub struct TranslationModel {
+    model: Arc<Mutex<translation::TranslationModel>>,
+}
+
+#[napi]
+impl TranslationModel {
+    /// Creates a model for the `{sourceLanguages, targetLanguages}`.
+    #[napi]
+    pub fn create(init: Value) -> Result<AsyncTask<Run<TranslationModel>>> {
+        let init: TranslationModelInit = from_js(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                model: Arc::new(Mutex::new(init.create()?)),
+            })
+        }))
+    }
+
+    /// Translates the `{inputs, sourceLanguage, targetLanguage}`.
+    #[napi]
+    pub fn translate(&self, init: Value) -> Result<AsyncTask<Run<Vec<String>>>> {
+        let init: TranslationInit = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run(move || {
+            bertml::translate(&model.lock().unwrap(), &init)
+        }))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::sentence_embeddings::SentenceEmbeddingsModel;
+use crate::{from_js, run, Run};
+use bertml::{VectorIndexInit, VectorIndexLoadInit};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// An index of vectors that can be searched by similarity.
+#[napi]
+pub struct VectorIndex {
+    index: Arc<RwLock<bertml::VectorIndex>>,
+}
+
+#[napi]
+impl VectorIndex {
+    /// Creates an empty index with the `{dimension, modelId, metric, kind, ...}`.
+    #[napi]
+    pub fn create(init: Value) -> Result<AsyncTask<Run<VectorIndex>>> {
+        let init: VectorIndexInit = from_js(init)?;
+        Ok(run(move || {
+            Ok(Self {
+                index: Arc::new(RwLock::new(init.create()?)),
+            })
+        }))
+    }
+
+    /// Loads an index that was saved with `save` from the `{path, mmap}`.
+    #[napi]
+    pub fn load(init: Value) -> Result<AsyncTask<Run<VectorIndex>>> {
+        let init: VectorIndexLoadInit = from_js(init)?;
+        Ok(run(move || {
+            let index = bertml::VectorIndex::load(Path::new(&init.path), init.mmap)?;
+            Ok(Self {
+                index: Arc::new(RwLock::new(index)),
+            })
+        }))
+    }
+
+    /// Adds the vectors with the ids, replacing the vectors that already have the ids. Resolves
+    /// with the number of vectors in the index.
+    #[napi]
+    pub fn add(&self, ids: Vec<String>, vectors: Vec<Float32Array>) -> AsyncTask<Run<u32>> {
+        let vectors = vectors.iter().map(|v| v.to_vec()).collect();
+        let index = self.index.clone();
+        run(move || Ok(index.write().unwrap().add_all(ids, vectors)? as u32))
+    }
+
+    /// Encodes the texts with the model and adds them with the ids. Resolves with the number of
+    /// vectors in the index.
+    #[napi]
+    pub fn add_texts(
+        &self,
+        model: &SentenceEmbeddingsModel,
+        ids: Vec<String>,
+        texts: Vec<String>,
+    ) -> AsyncTask<Run<u32>> {
+        let (model, index) = (model.model.clone(), self.index.clone());
+        run(move || {
+            let vectors = bertml::embed_sentences(&model.lock().unwrap(), &texts, false)?;
+            Ok(index.write().unwrap().add_all(ids, vectors)? as u32)
+        })
+    }
+
+    /// Removes the vectors with the ids and resolves with the number that were removed.
+    #[napi]
+    pub fn remove(&self, ids: Vec<String>) -> AsyncTask<Run<u32>> {
+        let index = self.index.clone();
+        run(move || {
+            let mut index = index.write().unwrap();
+            Ok(ids.iter().filter(|id| index.remove(id)).count() as u32)
+        })
+    }
+
+    /// Finds the `k` most similar vectors to each of the queries.
+    #[napi]
+    pub fn search(&self, queries: Vec<Float32Array>, k: u32) -> AsyncTask<Run<Value>> {
+        let queries = queries.iter().map(|q| q.to_vec()).collect();
+        let index = self.index.clone();
+        run(move || {
+            let index = index.read().unwrap();
+            Ok(serde_json::to_value(
+                index.search_all(queries, k as usize)?,
+            )?)
+        })
+    }
+
+    /// Encodes the queries with the model and finds the `k` most similar vectors to each.
+    #[napi]
+    pub fn search_texts(
+        &self,
+        model: &SentenceEmbeddingsModel,
+        queries: Vec<String>,
+        k: u32,
+    ) -> AsyncTask<Run<Value>> {
+        let (model, index) = (model.model.clone(), self.index.clone());
+        run(move || {
+            let queries = bertml::embed_sentences(&model.lock().unwrap(), &queries, false)?;
+            let index = index.read().unwrap();
+            Ok(serde_json::to_value(
+                index.search_all(queries, k as usize)?,
+            )?)
+        })
+    }
+
+    /// Writes the index to the file at the path.
+    #[napi]
+    pub fn save(&self, path: String) -> AsyncTask<Run<()>> {
+        let index = self.index.clone();
+        run(move || index.read().unwrap().save(Path::new(&path)))
+    }
+
+    /// Gets the configuration and size of the index.
+    #[napi]
+    pub fn info(&self) -> AsyncTask<Run<Value>> {
+        let index = self.index.clone();
+        run(move || Ok(serde_json::to_value(index.read().unwrap().info())?))
+    }
+}
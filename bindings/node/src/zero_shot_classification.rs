@@ -0,0 +1,82 @@
+use crate::sentiment::AspectSentimentInit;
+use crate::{from_js, run, run_json, Run};
+use anyhow::Context;
+use bertml::{AspectClassifier, ZeroShotDistributionInput, ZeroShotInput};
+use napi::bindgen_prelude::*;
+use napi::{Env, JsObject};
+use napi_derive::napi;
+use rust_bert::pipelines::zero_shot_classification;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// A model for classifying inputs with labels that it was not trained on.
+#[napi]
+pub struct ZeroShotClassificationModel {
+    model: Arc<Mutex<zero_shot_classification::ZeroShotClassificationModel>>,
+}
+
+#[napi]
+impl ZeroShotClassificationModel {
+    #[napi]
+    pub fn create() -> AsyncTask<Run<ZeroShotClassificationModel>> {
+        run(|| {
+            let model =
+                zero_shot_classification::ZeroShotClassificationModel::new(Default::default())
+                    .context("Failed to create zero shot classification model.")?;
+            Ok(Self {
+                model: Arc::new(Mutex::new(model)),
+            })
+        })
+    }
+
+    /// Predicts the most likely of the labels for each input.
+    #[napi]
+    pub fn predict(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: ZeroShotInput = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            bertml::classify(&model.lock().unwrap(), &init)
+        }))
+    }
+
+    /// Predicts the entailment score of every label for each input.
+    #[napi]
+    pub fn predict_multilabel(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: ZeroShotInput = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            bertml::classify_multilabel(&model.lock().unwrap(), &init)
+        }))
+    }
+
+    /// Predicts the probability of every label for each input, ranked from the most to least
+    /// probable label.
+    #[napi]
+    pub fn predict_distribution(&self, init: Value) -> Result<AsyncTask<Run<Value>>> {
+        let init: ZeroShotDistributionInput = from_js(init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            bertml::classify_distribution(&model.lock().unwrap(), &init)
+        }))
+    }
+
+    /// Predicts the sentiment towards each aspect mentioned in the inputs by asking the model
+    /// whether "The {aspect} is positive." or negative.
+    #[napi(
+        ts_args_type = "init: { inputs: string[], aspects?: string[], posModel?: POSModel, maxLength?: number }"
+    )]
+    pub fn predict_aspects(&self, env: Env, init: JsObject) -> Result<AsyncTask<Run<Value>>> {
+        let init = AspectSentimentInit::from_js(env, init)?;
+        let model = self.model.clone();
+        Ok(run_json(move || {
+            let aspects = init.resolve_aspects()?;
+            let model = model.lock().unwrap();
+            Ok(bertml::predict_aspects(
+                AspectClassifier::ZeroShot(&model),
+                &init.inputs,
+                &aspects,
+                init.max_length,
+            ))
+        }))
+    }
+}
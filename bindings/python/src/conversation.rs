@@ -0,0 +1,93 @@
+use crate::run;
+use anyhow::Context;
+use pyo3::prelude::*;
+use rust_bert::pipelines::conversation;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// A model for holding conversations.
+#[pyclass(module = "bertml")]
+pub struct ConversationModel {
+    model: Mutex<conversation::ConversationModel>,
+}
+
+#[pymethods]
+impl ConversationModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            conversation::ConversationModel::new(Default::default())
+                .context("Failed to create conversation model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    fn create_conversation_manager(slf: PyRef<'_, Self>) -> ConversationManager {
+        ConversationManager {
+            model: slf.into(),
+            manager: Mutex::new(conversation::ConversationManager::new()),
+        }
+    }
+}
+
+/// Manages the conversations of a conversation model.
+#[pyclass(module = "bertml")]
+pub struct ConversationManager {
+    model: Py<ConversationModel>,
+    manager: Mutex<conversation::ConversationManager>,
+}
+
+#[pymethods]
+impl ConversationManager {
+    /// Creates a conversation, which keeps the history of its messages and responses when
+    /// `include_history` is true.
+    #[pyo3(signature = (include_history = false))]
+    fn create_conversation(slf: PyRef<'_, Self>, include_history: bool) -> Conversation {
+        let id = slf.manager.lock().unwrap().create_empty();
+        Conversation {
+            manager: slf.into(),
+            id,
+            include_history,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// A conversation and its history.
+#[pyclass(module = "bertml")]
+pub struct Conversation {
+    manager: Py<ConversationManager>,
+    id: Uuid,
+    include_history: bool,
+    history: Vec<(String, String)>,
+}
+
+#[pymethods]
+impl Conversation {
+    /// The messages that were sent and the responses to them.
+    #[getter]
+    fn history(&self) -> Vec<(String, String)> {
+        self.history.clone()
+    }
+
+    /// Sends the message to the model and returns its response.
+    fn send_message(&mut self, py: Python, message: String) -> PyResult<String> {
+        let manager = self.manager.borrow(py);
+        let model = manager.model.borrow(py);
+        let (model, manager, id) = (&model.model, &manager.manager, &self.id);
+        let response = run(py, || {
+            bertml::send_message(
+                &model.lock().unwrap(),
+                &mut manager.lock().unwrap(),
+                id,
+                &message,
+            )
+        })?;
+        if self.include_history {
+            self.history.push((message, response.clone()));
+        }
+        Ok(response)
+    }
+}
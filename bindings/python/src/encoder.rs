@@ -0,0 +1,62 @@
+use crate::{from_py, from_py_or_default, run, tensor_to_numpy, tokenizer};
+use bertml::{BertModelInit, EncoderInit};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::Mutex;
+
+/// A BERT encoder without a task head, for reading its hidden states and attentions.
+#[pyclass(module = "bertml")]
+pub struct EncoderModel {
+    model: Mutex<bertml::EncoderModel>,
+}
+
+#[pymethods]
+impl EncoderModel {
+    #[new]
+    #[pyo3(signature = (init = None))]
+    fn new(py: Python, init: Option<&PyAny>) -> PyResult<Self> {
+        let init: BertModelInit = from_py_or_default(py, init)?;
+        let model = run(py, || bertml::EncoderModel::new(&init))?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Runs the encoder on the `{"inputs", "allLayers", "attentions"}` and returns a dict with
+    /// the same tensors as the tensor frame of the FFI as `float32` arrays, along with the
+    /// `tokenIds` of each input.
+    fn forward<'py>(&self, py: Python<'py>, init: &PyAny) -> PyResult<&'py PyDict> {
+        let init: EncoderInit = from_py(init)?;
+        let output = run(py, || self.model.lock().unwrap().forward(&init))?;
+
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "lastHiddenState",
+            tensor_to_numpy(py, &output.last_hidden_state)?,
+        )?;
+        if let Some(pooled) = &output.pooled_output {
+            dict.set_item("pooledOutput", tensor_to_numpy(py, pooled)?)?;
+        }
+        if let Some(layers) = &output.hidden_states {
+            dict.set_item("hiddenStates", tensor_to_numpy(py, layers)?)?;
+        }
+        if let Some(attentions) = &output.attentions {
+            dict.set_item("attentions", tensor_to_numpy(py, attentions)?)?;
+        }
+        dict.set_item("tokenIds", output.token_ids)?;
+        Ok(dict)
+    }
+
+    fn tokenize(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        tokenizer::tokenize(py, &self.model, init)
+    }
+
+    fn decode(&self, py: Python, init: &PyAny) -> PyResult<Vec<String>> {
+        tokenizer::decode(py, &self.model, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    fn max_length(&self) -> usize {
+        self.model.lock().unwrap().max_length()
+    }
+}
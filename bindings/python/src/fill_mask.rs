@@ -0,0 +1,48 @@
+use crate::{from_py, from_py_or_default, run, to_py, tokenizer};
+use bertml::{BertModelInit, FillMaskInit};
+use pyo3::prelude::*;
+use std::sync::Mutex;
+
+/// A BERT masked language model that predicts the most probable tokens for masks.
+#[pyclass(module = "bertml")]
+pub struct FillMaskModel {
+    model: Mutex<bertml::FillMaskModel>,
+}
+
+#[pymethods]
+impl FillMaskModel {
+    #[new]
+    #[pyo3(signature = (init = None))]
+    fn new(py: Python, init: Option<&PyAny>) -> PyResult<Self> {
+        let init: BertModelInit = from_py_or_default(py, init)?;
+        let model = run(py, || bertml::FillMaskModel::new(&init))?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Gets the `topK` candidates for each mask of each input.
+    fn predict(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        let init: FillMaskInit = from_py(init)?;
+        let candidates = run(py, || {
+            self.model
+                .lock()
+                .unwrap()
+                .candidates(&init.inputs, init.mask_token(), init.top_k)
+        })?;
+        to_py(py, &candidates)
+    }
+
+    fn tokenize(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        tokenizer::tokenize(py, &self.model, init)
+    }
+
+    fn decode(&self, py: Python, init: &PyAny) -> PyResult<Vec<String>> {
+        tokenizer::decode(py, &self.model, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    fn max_length(&self) -> usize {
+        self.model.lock().unwrap().max_length()
+    }
+}
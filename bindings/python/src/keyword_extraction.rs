@@ -0,0 +1,32 @@
+use crate::{from_py_or_default, run, to_py};
+use bertml::KeywordExtractionModelInit;
+use pyo3::prelude::*;
+use rust_bert::pipelines::keywords_extraction;
+use std::sync::Mutex;
+
+/// A model for extracting the keyphrases of inputs.
+#[pyclass(module = "bertml")]
+pub struct KeywordExtractionModel {
+    model: Mutex<keywords_extraction::KeywordExtractionModel<'static>>,
+}
+
+#[pymethods]
+impl KeywordExtractionModel {
+    #[new]
+    #[pyo3(signature = (init = None))]
+    fn new(py: Python, init: Option<&PyAny>) -> PyResult<Self> {
+        let init: KeywordExtractionModelInit = from_py_or_default(py, init)?;
+        let model = run(py, || init.create())?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Extracts the keyphrases of each of the inputs.
+    fn extract(&self, py: Python, inputs: Vec<String>) -> PyResult<PyObject> {
+        let keywords = run(py, || {
+            bertml::extract_keywords(&self.model.lock().unwrap(), &inputs)
+        })?;
+        to_py(py, &keywords)
+    }
+}
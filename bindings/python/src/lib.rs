@@ -0,0 +1,102 @@
+//! Python bindings to the typed API of bertml.
+//!
+//! The configs and inputs are the same objects that the Deno bindings take (with camelCase keys)
+//! and the results have the same shapes, so both go through the same preprocessing, model
+//! loading and output conversion in `bertml`. Inference runs without the GIL.
+
+mod conversation;
+mod encoder;
+mod fill_mask;
+mod keyword_extraction;
+mod ner;
+mod pos_tagging;
+mod qa;
+mod sentence_embeddings;
+mod sentiment;
+mod summarization;
+mod text_generation;
+mod tokenizer;
+mod translation;
+mod vector_index;
+mod zero_shot_classification;
+
+use numpy::PyArray1;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tch::{Device, Kind, Tensor};
+
+create_exception!(bertml, BertMLError, PyException);
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    BertMLError::new_err(e.to_string())
+}
+
+/// Runs `f` without the GIL so that other Python threads keep running during inference.
+fn run<T, F>(py: Python, f: F) -> PyResult<T>
+where
+    T: Send,
+    F: FnOnce() -> Result<T, anyhow::Error> + Send,
+{
+    py.allow_threads(f).map_err(to_py_err)
+}
+
+/// Converts a Python object into one of the config or input structs of bertml.
+fn from_py<T: DeserializeOwned>(value: &PyAny) -> PyResult<T> {
+    pythonize::depythonize(value).map_err(|e| BertMLError::new_err(e.to_string()))
+}
+
+/// Same as `from_py`, where `None` is converted like an empty dict.
+fn from_py_or_default<T: DeserializeOwned>(py: Python, value: Option<&PyAny>) -> PyResult<T> {
+    from_py(value.unwrap_or_else(|| PyDict::new(py).as_ref()))
+}
+
+/// Converts one of the result structs of bertml into Python objects.
+fn to_py<T: Serialize + ?Sized>(py: Python, value: &T) -> PyResult<PyObject> {
+    pythonize::pythonize(py, value).map_err(|e| BertMLError::new_err(e.to_string()))
+}
+
+/// Copies the tensor to the CPU into a `float32` array of the same shape.
+fn tensor_to_numpy(py: Python, tensor: &Tensor) -> PyResult<PyObject> {
+    let tensor = tensor
+        .to_kind(Kind::Float)
+        .to_device(Device::Cpu)
+        .contiguous();
+    let numel = tensor.numel();
+    let mut values = vec![0f32; numel];
+    tensor.copy_data(&mut values, numel);
+    let shape = tensor
+        .size()
+        .into_iter()
+        .map(|d| d as usize)
+        .collect::<Vec<_>>();
+    Ok(PyArray1::from_vec(py, values).reshape(shape)?.to_object(py))
+}
+
+#[pymodule]
+#[pyo3(name = "bertml")]
+fn bertml_python(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("BertMLError", py.get_type::<BertMLError>())?;
+    m.add_class::<conversation::ConversationModel>()?;
+    m.add_class::<conversation::ConversationManager>()?;
+    m.add_class::<conversation::Conversation>()?;
+    m.add_class::<encoder::EncoderModel>()?;
+    m.add_class::<fill_mask::FillMaskModel>()?;
+    m.add_class::<keyword_extraction::KeywordExtractionModel>()?;
+    m.add_class::<ner::NERModel>()?;
+    m.add_class::<pos_tagging::POSModel>()?;
+    m.add_class::<qa::QAModel>()?;
+    m.add_class::<qa::QACorpus>()?;
+    m.add_class::<sentence_embeddings::SentenceEmbeddingsModel>()?;
+    m.add_class::<sentiment::SentimentModel>()?;
+    m.add_class::<summarization::SummarizationModel>()?;
+    m.add_class::<text_generation::TextGenerationModel>()?;
+    m.add_class::<tokenizer::Tokenizer>()?;
+    m.add_class::<translation::TranslationModel>()?;
+    m.add_class::<vector_index::VectorIndex>()?;
+    m.add_class::<zero_shot_classification::ZeroShotClassificationModel>()?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::{run, to_py};
+use anyhow::Context;
+use pyo3::prelude::*;
+use rust_bert::pipelines::ner;
+use std::sync::Mutex;
+
+/// A model for finding the named entities in inputs.
+#[pyclass(module = "bertml")]
+pub struct NERModel {
+    model: Mutex<ner::NERModel>,
+}
+
+#[pymethods]
+impl NERModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            ner::NERModel::new(Default::default()).context("Failed to create NER model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Predicts the entities of each of the inputs.
+    fn predict(&self, py: Python, inputs: Vec<String>) -> PyResult<PyObject> {
+        let entities = run(py, || {
+            Ok(bertml::predict_entities(
+                &self.model.lock().unwrap(),
+                &inputs,
+            ))
+        })?;
+        to_py(py, &entities)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{from_py, run, to_py};
+use anyhow::Context;
+use bertml::{POSPredictInit, TagSet};
+use pyo3::prelude::*;
+use rust_bert::pipelines::pos_tagging;
+use std::sync::Mutex;
+
+/// A model for finding the part of speech of the words in inputs.
+#[pyclass(module = "bertml")]
+pub struct POSModel {
+    pub(crate) model: Mutex<pos_tagging::POSModel>,
+}
+
+#[pymethods]
+impl POSModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            pos_tagging::POSModel::new(Default::default())
+                .context("Failed to load Parts of Speech Tagging model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Predicts the parts of speech of the words in each of the inputs, with the labels of the
+    /// tag set (`"ptb"` or `"ud"`).
+    #[pyo3(signature = (inputs, tag_set = None))]
+    fn predict(
+        &self,
+        py: Python,
+        inputs: Vec<String>,
+        tag_set: Option<&PyAny>,
+    ) -> PyResult<PyObject> {
+        let init = POSPredictInit {
+            inputs,
+            tag_set: match tag_set {
+                Some(tag_set) => from_py::<TagSet>(tag_set)?,
+                None => TagSet::default(),
+            },
+        };
+        let tags = run(py, || {
+            Ok(bertml::predict_tags(&self.model.lock().unwrap(), &init))
+        })?;
+        to_py(py, &tags)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{from_py, from_py_or_default, run, to_py};
+use bertml::{
+    JSPassage, JSQaContextInput, JSQaInput, PassageCorpus, QAModelInit, QaCorpusQueryInit,
+};
+use pyo3::prelude::*;
+use rust_bert::pipelines::question_answering::QaInput;
+use std::sync::Mutex;
+
+/// A model used for finding the answer within a context for inputs.
+#[pyclass(module = "bertml")]
+pub struct QAModel {
+    model: Mutex<bertml::QAModel>,
+}
+
+#[pymethods]
+impl QAModel {
+    #[new]
+    #[pyo3(signature = (init = None))]
+    fn new(py: Python, init: Option<&PyAny>) -> PyResult<Self> {
+        let init: QAModelInit = from_py_or_default(py, init)?;
+        let model = run(py, || init.create())?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Answers each `{"question", "context"}` of the question groups with the n top answers.
+    #[pyo3(signature = (question_groups, n_top_answers = 1, batch_size = 32, no_answer_threshold = None))]
+    fn query(
+        &self,
+        py: Python,
+        question_groups: &PyAny,
+        n_top_answers: usize,
+        batch_size: usize,
+        no_answer_threshold: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let inputs: Vec<JSQaInput> = from_py(question_groups)?;
+        let inputs = inputs.into_iter().map(QaInput::from).collect::<Vec<_>>();
+        let answers = run(py, || {
+            Ok(bertml::predict_answers(
+                &self.model.lock().unwrap(),
+                inputs,
+                n_top_answers,
+                batch_size,
+                no_answer_threshold,
+            ))
+        })?;
+        to_py(py, &answers)
+    }
+
+    /// Same as `query` except that each `{"context", "questions"}` gives a context once for all
+    /// of its questions. The answers are grouped by context and then by question.
+    #[pyo3(signature = (contexts, n_top_answers = 1, batch_size = 32, no_answer_threshold = None))]
+    fn query_contexts(
+        &self,
+        py: Python,
+        contexts: &PyAny,
+        n_top_answers: usize,
+        batch_size: usize,
+        no_answer_threshold: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let inputs: Vec<JSQaContextInput> = from_py(contexts)?;
+        let answers = run(py, || {
+            Ok(bertml::predict_context_answers(
+                &self.model.lock().unwrap(),
+                inputs,
+                n_top_answers,
+                batch_size,
+                no_answer_threshold,
+            ))
+        })?;
+        to_py(py, &answers)
+    }
+
+    /// Indexes the `{"id", "text"}` passages to ask questions about.
+    fn create_corpus(slf: PyRef<'_, Self>, passages: &PyAny) -> PyResult<QACorpus> {
+        let passages: Vec<JSPassage> = from_py(passages)?;
+        let corpus = run(slf.py(), || Ok(PassageCorpus::new(passages)))?;
+        Ok(QACorpus {
+            model: slf.into(),
+            corpus,
+        })
+    }
+}
+
+/// Passages indexed for BM25 retrieval that the questions are answered from.
+#[pyclass(module = "bertml")]
+pub struct QACorpus {
+    model: Py<QAModel>,
+    corpus: PassageCorpus,
+}
+
+#[pymethods]
+impl QACorpus {
+    /// Retrieves the passages that match each question the best with BM25 and returns the top
+    /// answers found in those passages.
+    #[pyo3(signature = (questions, n_passages = 3, n_top_answers = 1, batch_size = 32, no_answer_threshold = None))]
+    fn query(
+        &self,
+        py: Python,
+        questions: Vec<String>,
+        n_passages: usize,
+        n_top_answers: usize,
+        batch_size: usize,
+        no_answer_threshold: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let init = QaCorpusQueryInit {
+            questions,
+            n_passages,
+            n_top_answers,
+            batch_size,
+            no_answer_threshold,
+        };
+        let model = self.model.borrow(py);
+        let model = &model.model;
+        let answers = run(py, || Ok(self.corpus.query(&model.lock().unwrap(), &init)))?;
+        to_py(py, &answers)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{from_py, from_py_or_default, run};
+use bertml::{SentenceEmbeddingsInit, SentenceEmbeddingsModelInit};
+use numpy::PyArray2;
+use pyo3::prelude::*;
+use rust_bert::pipelines::sentence_embeddings;
+use std::sync::Mutex;
+
+/// A model for encoding sentences into vectors.
+#[pyclass(module = "bertml")]
+pub struct SentenceEmbeddingsModel {
+    pub(crate) model: Mutex<sentence_embeddings::SentenceEmbeddingsModel>,
+}
+
+impl SentenceEmbeddingsModel {
+    pub(crate) fn embed(
+        &self,
+        py: Python,
+        inputs: &[String],
+        normalize: bool,
+    ) -> PyResult<Vec<Vec<f32>>> {
+        run(py, || {
+            bertml::embed_sentences(&self.model.lock().unwrap(), inputs, normalize)
+        })
+    }
+}
+
+#[pymethods]
+impl SentenceEmbeddingsModel {
+    #[new]
+    #[pyo3(signature = (init = None))]
+    fn new(py: Python, init: Option<&PyAny>) -> PyResult<Self> {
+        let init: SentenceEmbeddingsModelInit = from_py_or_default(py, init)?;
+        let model = run(py, || init.create())?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Encodes the `{"inputs", "normalize"}` into a `float32` array with one row per input.
+    fn encode<'py>(&self, py: Python<'py>, init: &PyAny) -> PyResult<&'py PyArray2<f32>> {
+        let init: SentenceEmbeddingsInit = from_py(init)?;
+        let embeddings = self.embed(py, &init.inputs, init.normalize)?;
+        Ok(PyArray2::from_vec2(py, &embeddings)?)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::pos_tagging::POSModel;
+use crate::{run, to_py, BertMLError};
+use anyhow::Context;
+use bertml::AspectClassifier;
+use pyo3::prelude::*;
+use rust_bert::pipelines::sentiment;
+use std::sync::Mutex;
+
+/// Gets the aspects of each input like `aspect_sentiment_predict`, either the given aspects for
+/// every input or the noun phrases that the POS model finds.
+pub(crate) fn resolve_aspects(
+    py: Python,
+    inputs: &[String],
+    aspects: Option<Vec<String>>,
+    pos_model: Option<PyRef<'_, POSModel>>,
+) -> PyResult<Vec<Vec<String>>> {
+    match (aspects, pos_model) {
+        (Some(aspects), _) => Ok(vec![aspects; inputs.len()]),
+        (None, Some(pos_model)) => {
+            let pos_model = &pos_model.model;
+            run(py, || {
+                Ok(bertml::extract_noun_phrases(
+                    &pos_model.lock().unwrap(),
+                    inputs,
+                ))
+            })
+        }
+        (None, None) => Err(BertMLError::new_err(
+            "Expected either aspects or a POS model to extract aspects with.",
+        )),
+    }
+}
+
+/// A model used to calculate the sentiment of inputs.
+#[pyclass(module = "bertml")]
+pub struct SentimentModel {
+    model: Mutex<sentiment::SentimentModel>,
+}
+
+#[pymethods]
+impl SentimentModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            sentiment::SentimentModel::new(Default::default())
+                .context("Failed to load sentiment model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Predicts the sentiments of the inputs.
+    fn predict(&self, py: Python, inputs: Vec<String>) -> PyResult<PyObject> {
+        let sentiments = run(py, || {
+            Ok(bertml::predict_sentiments(
+                &self.model.lock().unwrap(),
+                &inputs,
+            ))
+        })?;
+        to_py(py, &sentiments)
+    }
+
+    /// Splits each of the documents into sentences and predicts the sentiment of each sentence
+    /// along with aggregate statistics for the whole document.
+    fn predict_documents(&self, py: Python, documents: Vec<String>) -> PyResult<PyObject> {
+        let documents = run(py, || {
+            Ok(bertml::predict_documents(
+                &self.model.lock().unwrap(),
+                &documents,
+            ))
+        })?;
+        to_py(py, &documents)
+    }
+
+    /// Predicts the sentiment towards each aspect mentioned in the inputs.
+    #[pyo3(signature = (inputs, aspects = None, pos_model = None))]
+    fn predict_aspects(
+        &self,
+        py: Python,
+        inputs: Vec<String>,
+        aspects: Option<Vec<String>>,
+        pos_model: Option<PyRef<'_, POSModel>>,
+    ) -> PyResult<PyObject> {
+        let aspects = resolve_aspects(py, &inputs, aspects, pos_model)?;
+        let outputs = run(py, || {
+            let model = self.model.lock().unwrap();
+            Ok(bertml::predict_aspects(
+                AspectClassifier::Sentiment(&model),
+                &inputs,
+                &aspects,
+                128,
+            ))
+        })?;
+        to_py(py, &outputs)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::run;
+use anyhow::Context;
+use pyo3::prelude::*;
+use rust_bert::pipelines::summarization;
+use std::sync::Mutex;
+
+/// A model for summarizing inputs.
+#[pyclass(module = "bertml")]
+pub struct SummarizationModel {
+    model: Mutex<summarization::SummarizationModel>,
+}
+
+#[pymethods]
+impl SummarizationModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            summarization::SummarizationModel::new(Default::default())
+                .context("Failed to create summarization model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Summarizes each of the inputs.
+    fn summarize(&self, py: Python, inputs: Vec<String>) -> PyResult<Vec<String>> {
+        run(py, || {
+            Ok(bertml::summarize(&self.model.lock().unwrap(), &inputs))
+        })
+    }
+}
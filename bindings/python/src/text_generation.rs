@@ -0,0 +1,34 @@
+use crate::{from_py, run};
+use anyhow::Context;
+use bertml::TextGenerationInit;
+use pyo3::prelude::*;
+use rust_bert::pipelines::text_generation;
+use std::sync::Mutex;
+
+/// A model for generating continuations of inputs.
+#[pyclass(module = "bertml")]
+pub struct TextGenerationModel {
+    model: Mutex<text_generation::TextGenerationModel>,
+}
+
+#[pymethods]
+impl TextGenerationModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            text_generation::TextGenerationModel::new(Default::default())
+                .context("Failed to create text generation model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Generates a continuation of each of the `{"inputs", "prefix"}`.
+    fn generate(&self, py: Python, init: &PyAny) -> PyResult<Vec<String>> {
+        let init: TextGenerationInit = from_py(init)?;
+        run(py, || {
+            Ok(bertml::generate(&self.model.lock().unwrap(), &init))
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{from_py, run, to_py};
+use bertml::{DecodeInit, TokenizeInit, TokenizerInit};
+use pyo3::prelude::*;
+use rust_bert::pipelines::common::TokenizerOption;
+use std::sync::Mutex;
+
+/// The models that expose their tokenizer, like `with_tokenizer` of the FFI.
+pub(crate) trait WithTokenizer: Send {
+    fn tokenizer(&self) -> &TokenizerOption;
+    fn max_length(&self) -> usize;
+}
+
+impl WithTokenizer for bertml::StandaloneTokenizer {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+impl WithTokenizer for bertml::FillMaskModel {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+impl WithTokenizer for bertml::EncoderModel {
+    fn tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer()
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length()
+    }
+}
+
+/// Tokenizes the `{"inputs", "addSpecialTokens", "maxLength"}` with the tokenizer of the model.
+pub(crate) fn tokenize<T: WithTokenizer>(
+    py: Python,
+    model: &Mutex<T>,
+    init: &PyAny,
+) -> PyResult<PyObject> {
+    let init: TokenizeInit = from_py(init)?;
+    let tokenized = run(py, || {
+        let model = model.lock().unwrap();
+        Ok(bertml::tokenize_batch(
+            model.tokenizer(),
+            &init,
+            model.max_length(),
+        ))
+    })?;
+    to_py(py, &tokenized)
+}
+
+/// Decodes the `{"ids", "skipSpecialTokens", "cleanUpTokenizationSpaces"}` with the tokenizer of
+/// the model.
+pub(crate) fn decode<T: WithTokenizer>(
+    py: Python,
+    model: &Mutex<T>,
+    init: &PyAny,
+) -> PyResult<Vec<String>> {
+    let init: DecodeInit = from_py(init)?;
+    run(py, || {
+        Ok(bertml::decode_batch(
+            model.lock().unwrap().tokenizer(),
+            &init,
+        ))
+    })
+}
+
+/// A tokenizer that is loaded from vocab files without a model.
+#[pyclass(module = "bertml")]
+pub struct Tokenizer {
+    tokenizer: Mutex<bertml::StandaloneTokenizer>,
+}
+
+#[pymethods]
+impl Tokenizer {
+    #[new]
+    fn new(py: Python, init: &PyAny) -> PyResult<Self> {
+        let init: TokenizerInit = from_py(init)?;
+        let tokenizer = run(py, || init.create())?;
+        Ok(Self {
+            tokenizer: Mutex::new(tokenizer),
+        })
+    }
+
+    fn tokenize(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        tokenize(py, &self.tokenizer, init)
+    }
+
+    fn decode(&self, py: Python, init: &PyAny) -> PyResult<Vec<String>> {
+        decode(py, &self.tokenizer, init)
+    }
+
+    /// The maximum number of tokens of an input.
+    fn max_length(&self) -> usize {
+        self.tokenizer.lock().unwrap().max_length()
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{from_py, run};
+use bertml::{TranslationInit, TranslationModelInit};
+use pyo3::prelude::*;
+use rust_bert::pipelines::translation;
+use std::sync::Mutex;
+
+/// A model for translating text from any of its source languages to any of its target
+/// languages, where the languages are the indices of `Language` in the Deno bindings.
+#[pyclass(module = "bertml")]
+pub struct TranslationModel {
+    model: Mutex<translation::TranslationModel>,
+}
+
+#[pymethods]
+impl TranslationModel {
+    /// Creates a model for the `{"sourceLanguages", "targetLanguages"}`.
+    #[new]
+    fn new(py: Python, init: &PyAny) -> PyResult<Self> {
+        let init: TranslationModelInit = from_py(init)?;
+        let model = run(py, || init.create())?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Translates the `{"inputs", "sourceLanguage", "targetLanguage"}`.
+    fn translate(&self, py: Python, init: &PyAny) -> PyResult<Vec<String>> {
+        let init: TranslationInit = from_py(init)?;
+        run(py, || bertml::translate(&self.model.lock().unwrap(), &init))
+    }
+}
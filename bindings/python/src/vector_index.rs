@@ -0,0 +1,90 @@
+use crate::sentence_embeddings::SentenceEmbeddingsModel;
+use crate::{from_py, run, to_py, to_py_err};
+use bertml::{VectorIndexInit, VectorIndexLoadInit};
+use pyo3::prelude::*;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// An index of vectors that can be searched by similarity.
+#[pyclass(module = "bertml")]
+pub struct VectorIndex {
+    index: RwLock<bertml::VectorIndex>,
+}
+
+#[pymethods]
+impl VectorIndex {
+    /// Creates an empty index with the `{"dimension", "modelId", "metric", "kind", ...}`.
+    #[new]
+    fn new(init: &PyAny) -> PyResult<Self> {
+        let init: VectorIndexInit = from_py(init)?;
+        Ok(Self {
+            index: RwLock::new(init.create().map_err(to_py_err)?),
+        })
+    }
+
+    /// Loads an index that was saved with `save` from the `{"path", "mmap"}`.
+    #[staticmethod]
+    fn load(py: Python, init: &PyAny) -> PyResult<Self> {
+        let init: VectorIndexLoadInit = from_py(init)?;
+        let index = run(py, || {
+            bertml::VectorIndex::load(Path::new(&init.path), init.mmap)
+        })?;
+        Ok(Self {
+            index: RwLock::new(index),
+        })
+    }
+
+    /// Adds the vectors with the ids, replacing the vectors that already have the ids. Returns
+    /// the number of vectors in the index.
+    fn add(&self, py: Python, ids: Vec<String>, vectors: Vec<Vec<f32>>) -> PyResult<usize> {
+        run(py, || self.index.write().unwrap().add_all(ids, vectors))
+    }
+
+    /// Encodes the texts with the model and adds them with the ids. Returns the number of
+    /// vectors in the index.
+    fn add_texts(
+        &self,
+        py: Python,
+        model: PyRef<'_, SentenceEmbeddingsModel>,
+        ids: Vec<String>,
+        texts: Vec<String>,
+    ) -> PyResult<usize> {
+        let vectors = model.embed(py, &texts, false)?;
+        run(py, || self.index.write().unwrap().add_all(ids, vectors))
+    }
+
+    /// Removes the vectors with the ids and returns the number that were removed.
+    fn remove(&self, ids: Vec<String>) -> usize {
+        let mut index = self.index.write().unwrap();
+        ids.iter().filter(|id| index.remove(id)).count()
+    }
+
+    /// Finds the `k` most similar vectors to each of the queries.
+    fn search(&self, py: Python, queries: Vec<Vec<f32>>, k: usize) -> PyResult<PyObject> {
+        let index = self.index.read().unwrap();
+        let results = run(py, || index.search_all(queries, k))?;
+        to_py(py, &results)
+    }
+
+    /// Encodes the queries with the model and finds the `k` most similar vectors to each.
+    fn search_texts(
+        &self,
+        py: Python,
+        model: PyRef<'_, SentenceEmbeddingsModel>,
+        queries: Vec<String>,
+        k: usize,
+    ) -> PyResult<PyObject> {
+        let queries = model.embed(py, &queries, false)?;
+        self.search(py, queries, k)
+    }
+
+    /// Writes the index to the file at the path.
+    fn save(&self, py: Python, path: &str) -> PyResult<()> {
+        run(py, || self.index.read().unwrap().save(Path::new(path)))
+    }
+
+    /// Gets the configuration and size of the index.
+    fn info(&self, py: Python) -> PyResult<PyObject> {
+        to_py(py, &self.index.read().unwrap().info())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::pos_tagging::POSModel;
+use crate::sentiment::resolve_aspects;
+use crate::{from_py, run, to_py};
+use anyhow::Context;
+use bertml::{AspectClassifier, ZeroShotDistributionInput, ZeroShotInput};
+use pyo3::prelude::*;
+use rust_bert::pipelines::zero_shot_classification;
+use std::sync::Mutex;
+
+/// A model for classifying inputs with labels that it was not trained on.
+#[pyclass(module = "bertml")]
+pub struct ZeroShotClassificationModel {
+    model: Mutex<zero_shot_classification::ZeroShotClassificationModel>,
+}
+
+#[pymethods]
+impl ZeroShotClassificationModel {
+    #[new]
+    fn new(py: Python) -> PyResult<Self> {
+        let model = run(py, || {
+            zero_shot_classification::ZeroShotClassificationModel::new(Default::default())
+                .context("Failed to create zero shot classification model.")
+        })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+
+    /// Predicts the most likely of the labels for each input.
+    fn predict(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        let init: ZeroShotInput = from_py(init)?;
+        let labels = run(py, || bertml::classify(&self.model.lock().unwrap(), &init))?;
+        to_py(py, &labels)
+    }
+
+    /// Predicts the entailment score of every label for each input.
+    fn predict_multilabel(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        let init: ZeroShotInput = from_py(init)?;
+        let labels = run(py, || {
+            bertml::classify_multilabel(&self.model.lock().unwrap(), &init)
+        })?;
+        to_py(py, &labels)
+    }
+
+    /// Predicts the probability of every label for each input, ranked from the most to least
+    /// probable label.
+    fn predict_distribution(&self, py: Python, init: &PyAny) -> PyResult<PyObject> {
+        let init: ZeroShotDistributionInput = from_py(init)?;
+        let distributions = run(py, || {
+            bertml::classify_distribution(&self.model.lock().unwrap(), &init)
+        })?;
+        to_py(py, &distributions)
+    }
+
+    /// Predicts the sentiment towards each aspect mentioned in the inputs by asking the model
+    /// whether "The {aspect} is positive." or negative.
+    #[pyo3(signature = (inputs, aspects = None, pos_model = None, max_length = 128))]
+    fn predict_aspects(
+        &self,
+        py: Python,
+        inputs: Vec<String>,
+        aspects: Option<Vec<String>>,
+        pos_model: Option<PyRef<'_, POSModel>>,
+        max_length: usize,
+    ) -> PyResult<PyObject> {
+        let aspects = resolve_aspects(py, &inputs, aspects, pos_model)?;
+        let outputs = run(py, || {
+            let model = self.model.lock().unwrap();
+            Ok(bertml::predict_aspects(
+                AspectClassifier::ZeroShot(&model),
+                &inputs,
+                &aspects,
+                max_length,
+            ))
+        })?;
+        to_py(py, &outputs)
+    }
+}
@@ -0,0 +1,81 @@
+//! Passes the resolved versions of rust-bert, tch and libtorch and the enabled features to
+//! `bertml_info`. A version that can't be found is left unset instead of guessed.
+
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Finds the versions of the direct dependencies of bertml with `cargo metadata`, which resolves
+/// them the same way as the build.
+fn dependency_versions(manifest_dir: &str) -> Option<Vec<(String, String)>> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1"])
+        .current_dir(manifest_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout).ok()?;
+
+    // The root is bertml since the manifest dir is its package
+    let root = &metadata["resolve"]["root"];
+    let node = metadata["resolve"]["nodes"]
+        .as_array()?
+        .iter()
+        .find(|node| node["id"] == *root)?;
+
+    Some(
+        node["deps"]
+            .as_array()?
+            .iter()
+            .filter_map(|dep| {
+                let package = metadata["packages"]
+                    .as_array()?
+                    .iter()
+                    .find(|package| package["id"] == dep["pkg"])?;
+                Some((
+                    package["name"].as_str()?.to_string(),
+                    package["version"].as_str()?.to_string(),
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Reads the version of the libtorch that torch-sys links, from the `build-version` file that
+/// libtorch distributions ship next to `lib`.
+fn libtorch_version() -> Option<String> {
+    let libtorch = env::var("LIBTORCH").ok()?;
+    let version = fs::read_to_string(Path::new(&libtorch).join("build-version")).ok()?;
+    Some(version.trim().to_string())
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-env-changed=LIBTORCH");
+
+    let versions = dependency_versions(&manifest_dir).unwrap_or_default();
+    for (name, var) in [("rust-bert", "RUST_BERT_VERSION"), ("tch", "TCH_VERSION")] {
+        if let Some((_, version)) = versions.iter().find(|(dep, _)| dep == name) {
+            println!("cargo:rustc-env={}={}", var, version);
+        }
+    }
+    if let Some(version) = libtorch_version() {
+        println!("cargo:rustc-env=LIBTORCH_VERSION={}", version);
+    }
+
+    let mut features = env::vars()
+        .filter_map(|(var, _)| {
+            let feature = var.strip_prefix("CARGO_FEATURE_")?;
+            Some(feature.to_lowercase().replace('_', "-"))
+        })
+        .collect::<Vec<_>>();
+    features.sort();
+    println!("cargo:rustc-env=BERTML_FEATURES={}", features.join(","));
+}
@@ -0,0 +1,64 @@
+/**
+ * Generates `symbols.ts` from the symbol manifest of a build of the library,
+ * so that the symbol table always matches the exported functions.
+ *
+ * ```sh
+ * cargo build --release
+ * deno run --unstable --allow-ffi --allow-write generate_symbols.ts target/release/libbertml.so
+ * ```
+ */
+
+interface ManifestSymbol {
+  name: string;
+  parameters: string[];
+  result: string;
+}
+
+const [path, output = "symbols.ts"] = Deno.args;
+if (path === undefined) {
+  console.error("Usage: generate_symbols.ts <library> [output]");
+  Deno.exit(1);
+}
+
+const lib = Deno.dlopen(path, {
+  bertml_symbols: { parameters: [], result: "isize" },
+  fill_result: { parameters: ["buffer", "usize"], result: "void" },
+});
+const len = Number(lib.symbols.bertml_symbols());
+const buf = new Uint8Array(len);
+lib.symbols.fill_result(buf, len);
+lib.close();
+const manifest: ManifestSymbol[] = JSON.parse(new TextDecoder().decode(buf));
+
+const quote = (s: string) => JSON.stringify(s);
+const lines = [
+  "// Generated by `generate_symbols.ts` from the symbol manifest, do not edit.",
+  'import type { TypedDLOpenDynamicLib } from "https://deno.land/x/typedffi@v0.1.2/mod.ts";',
+  "",
+  "/**",
+  " * The exported functions of the library. Every call is nonblocking so that",
+  " * inference doesn't block the event loop.",
+  " */",
+  "export const symbolDefinitions = {",
+];
+for (const { name, parameters, result } of manifest) {
+  lines.push(
+    `  ${name}: {`,
+    `    parameters: [${parameters.map(quote).join(", ")}],`,
+    `    result: ${quote(result)},`,
+    "    nonblocking: true,",
+    "  },",
+  );
+}
+lines.push(
+  "} as const;",
+  "",
+  "/** The typed native functions of `symbolDefinitions`. */",
+  "export type FFISymbols = TypedDLOpenDynamicLib<",
+  "  typeof symbolDefinitions",
+  '>["symbols"];',
+  "",
+);
+
+await Deno.writeTextFile(output, lines.join("\n"));
+console.log(`Wrote ${manifest.length} symbols to ${output}.`);
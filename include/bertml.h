@@ -0,0 +1,264 @@
+#ifndef BERTML_H
+#define BERTML_H
+
+/*
+ * The C ABI of bertml.
+ *
+ * Models and resources are referred to by resource ids (rids). Structured inputs are encoded
+ * with the codec of the library (JSON by default, see `set_codec`) and passed as a pointer and
+ * a length.
+ *
+ * Functions that return `ptrdiff_t` return -1 on failure, after which `error_len` and
+ * `fill_error` give the error message. On success they return a rid, a count, 0 or the length
+ * of a result, which `fill_result` moves into a buffer of that length before the next call.
+ * Calls on the same library share the last result and error, so callers on several threads
+ * must serialize each call with its `fill_result` or `fill_error`.
+ */
+
+/* Generated with cbindgen:0.24.3 */
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The version of the C ABI in `include/bertml.h`. It is bumped whenever an exported function is
+ * removed or its signature, encoding or the meaning of its arguments changes.
+ */
+#define BERTML_ABI_VERSION 3
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+ptrdiff_t aspect_sentiment_predict(size_t rid, const uint8_t *buf, size_t buf_len);
+
+/**
+ * Returns the version of the C ABI of the library, to be compared with `BERTML_ABI_VERSION` of
+ * the header the caller was built with.
+ */
+uint32_t bertml_abi_version(void);
+
+/**
+ * Sets the result to the `LibraryInfo` of the library, which is always JSON like the symbol
+ * manifest.
+ */
+ptrdiff_t bertml_info(void);
+
+/**
+ * Sets the result to the symbol manifest, which is always JSON so that it can be read before
+ * the codec is known. `generate_symbols.ts` generates the symbol table of the Deno bindings
+ * from it.
+ */
+ptrdiff_t bertml_symbols(void);
+
+ptrdiff_t conversation_send(size_t model_rid,
+                            size_t manager_rid,
+                            size_t convo_rid,
+                            const uint8_t *text,
+                            size_t text_len);
+
+ptrdiff_t create_conversation(size_t rid);
+
+ptrdiff_t create_conversation_manager(void);
+
+ptrdiff_t create_conversation_model(void);
+
+ptrdiff_t create_encoder_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_fill_mask_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_keyword_extraction_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_ner_model(void);
+
+ptrdiff_t create_pos_model(void);
+
+ptrdiff_t create_qa_corpus(const uint8_t *buf, size_t buf_len);
+
+/**
+ * Creates a QA model and returns the resource number.
+ */
+ptrdiff_t create_qa_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_sentence_embeddings_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_sentiment_model(void);
+
+ptrdiff_t create_summarization_model(void);
+
+ptrdiff_t create_text_generation_model(void);
+
+ptrdiff_t create_tokenizer(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_translation_model(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_vector_index(const uint8_t *init, size_t init_len);
+
+ptrdiff_t create_zero_shot_model(void);
+
+/**
+ * Deletes the model at the rid.
+ */
+ptrdiff_t delete_model(size_t rid);
+
+/**
+ * Deletes the model resource (e.g. a vector index or conversation manager) at the rid.
+ */
+ptrdiff_t delete_model_resource(size_t rid);
+
+/**
+ * Sets the result to a tensor frame (see `TensorFrame`) with `lastHiddenState`
+ * (`[batch, tokens, hidden]`), `pooledOutput` (`[batch, hidden]`) and the optional layers.
+ */
+ptrdiff_t encoder_forward(size_t rid, const uint8_t *buf, size_t buf_len);
+
+/**
+ * Returns the length of the message of the last error, for a call that returned -1.
+ */
+size_t error_len(void);
+
+/**
+ * Moves the message of the last error (UTF-8) into the buffer, which must be `error_len()` long.
+ */
+void fill_error(uint8_t *buf, size_t buf_len);
+
+ptrdiff_t fill_mask_predict(size_t rid, const uint8_t *buf, size_t buf_len);
+
+/**
+ * Moves the result of the last call into the buffer, which must be as long as the length that
+ * the call returned.
+ */
+void fill_result(uint8_t *buf, size_t buf_len);
+
+ptrdiff_t get_codec(void);
+
+ptrdiff_t get_model_stats(void);
+
+ptrdiff_t keywords_extract(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t ner_predict(size_t rid, const uint8_t *input, size_t input_len);
+
+ptrdiff_t pos_predict(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t qa_corpus_query(size_t model_rid, size_t corpus_rid, const uint8_t *buf, size_t buf_len);
+
+/**
+ * Answers each question with the top answers of its context.
+ */
+ptrdiff_t qa_query(size_t rid,
+                   const uint8_t *init,
+                   size_t init_len,
+                   size_t answers_len,
+                   size_t batch_size);
+
+/**
+ * Same as `qa_query` except that each context is only sent once for all of its questions. The
+ * answers are grouped by context and then by question.
+ */
+ptrdiff_t qa_query_contexts(size_t rid,
+                            const uint8_t *init,
+                            size_t init_len,
+                            size_t answers_len,
+                            size_t batch_size);
+
+/**
+ * Encodes the inputs and sets the result to the embeddings as contiguous little-endian `f32`s,
+ * one row per input.
+ */
+ptrdiff_t sentence_embeddings_encode(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t sentiment_predict(size_t rid, const uint8_t *input, size_t input_len);
+
+ptrdiff_t sentiment_predict_document(size_t rid, const uint8_t *input, size_t input_len);
+
+/**
+ * Sets the codec of the library (0 = JSON, 1 = MessagePack, 2 = CBOR).
+ */
+ptrdiff_t set_codec(uint8_t codec);
+
+/**
+ * Sets the bytes of memory that the loaded models may take up, or removes the budget with 0.
+ * When a model doesn't fit, the least recently used models are unloaded and then loaded again
+ * from their config on their next use.
+ */
+ptrdiff_t set_model_memory_budget(size_t bytes);
+
+ptrdiff_t summarization_summarize(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t text_generation_generate(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t tokenizer_decode(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t tokenizer_encode(size_t rid, const uint8_t *buf, size_t buf_len);
+
+/**
+ * Returns the maximum number of tokens that the model accepts.
+ */
+ptrdiff_t tokenizer_max_length(size_t rid);
+
+ptrdiff_t translation_translate(size_t rid, const uint8_t *init, size_t init_len);
+
+/**
+ * Adds the vectors (contiguous little-endian `f32`s) with the ids (an array in the codec of the
+ * library) and returns the number of vectors in the index.
+ */
+ptrdiff_t vector_index_add(size_t rid,
+                           const uint8_t *ids,
+                           size_t ids_len,
+                           const uint8_t *vectors,
+                           size_t vectors_len);
+
+/**
+ * Encodes the texts with the sentence embeddings model and adds them with the ids.
+ */
+ptrdiff_t vector_index_add_texts(size_t rid, size_t model_rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t vector_index_info(size_t rid);
+
+/**
+ * Loads an index saved with `vector_index_save` and returns its resource id.
+ */
+ptrdiff_t vector_index_load(const uint8_t *init, size_t init_len);
+
+/**
+ * Removes the vectors with the ids (an array in the codec of the library) and returns the number
+ * that were removed.
+ */
+ptrdiff_t vector_index_remove(size_t rid, const uint8_t *ids, size_t ids_len);
+
+/**
+ * Saves the index to the file at the path (UTF-8 bytes).
+ */
+ptrdiff_t vector_index_save(size_t rid, const uint8_t *path, size_t path_len);
+
+/**
+ * Searches for the `k` most similar vectors to each of the queries (contiguous little-endian
+ * `f32`s).
+ */
+ptrdiff_t vector_index_search(size_t rid, const uint8_t *queries, size_t queries_len, size_t k);
+
+/**
+ * Encodes the queries with the sentence embeddings model and searches for the `k` most similar
+ * vectors to each of them.
+ */
+ptrdiff_t vector_index_search_texts(size_t rid,
+                                    size_t model_rid,
+                                    const uint8_t *buf,
+                                    size_t buf_len);
+
+ptrdiff_t zero_shot_predict(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t zero_shot_predict_distribution(size_t rid, const uint8_t *buf, size_t buf_len);
+
+ptrdiff_t zero_shot_predict_multilabel(size_t rid, const uint8_t *buf, size_t buf_len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* BERTML_H */
@@ -0,0 +1,74 @@
+//! The `#[ffi]` attribute of the exported functions of bertml.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Error, FnArg, ItemFn, ReturnType};
+
+/// Adds the `extern "C"` function to the symbol manifest of the library (`bertml::symbols`),
+/// with the FFI types of its parameters and result. The types must implement
+/// `bertml::FfiType`, so a function can't be exported with a type that the manifest can't
+/// describe.
+#[proc_macro_attribute]
+pub fn ffi(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::TokenStream::from(attr).span(),
+            "`#[ffi]` takes no arguments.",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let f = parse_macro_input!(item as ItemFn);
+    match symbol(&f) {
+        Ok(symbol) => quote!(#f #symbol).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn symbol(f: &ItemFn) -> Result<proc_macro2::TokenStream, Error> {
+    let is_c_abi = matches!(
+        &f.sig.abi,
+        Some(abi) if abi.name.as_ref().map_or(false, |name| name.value() == "C")
+    );
+    if !is_c_abi {
+        return Err(Error::new(
+            f.sig.span(),
+            "`#[ffi]` functions must be `extern \"C\"`.",
+        ));
+    }
+    if !f.attrs.iter().any(|attr| attr.path.is_ident("no_mangle")) {
+        return Err(Error::new(
+            f.sig.span(),
+            "`#[ffi]` functions must be `#[no_mangle]`.",
+        ));
+    }
+
+    let name = f.sig.ident.to_string();
+    let parameters = f
+        .sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => Ok(&arg.ty),
+            FnArg::Receiver(arg) => Err(Error::new(
+                arg.span(),
+                "`#[ffi]` functions can't take `self`.",
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = match &f.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+
+    Ok(quote! {
+        ::inventory::submit! {
+            crate::Symbol {
+                name: #name,
+                parameters: &[#(<#parameters as crate::FfiType>::NAME),*],
+                result: <#result as crate::FfiType>::NAME,
+            }
+        }
+    })
+}
@@ -1,15 +1,40 @@
 export { BertMLError } from "./error.ts";
 
 export { ModelManager } from "./model_manager.ts";
+export type {
+  AllocationInfo,
+  LibraryInfo,
+  ModelManagerOptions,
+  ModelStats,
+  ModelStatsReport,
+} from "./model_manager.ts";
+export type { Codec } from "./utils/codec.ts";
 export { Model } from "./model.ts";
 
-export { QAModel } from "./models/qa.ts";
-export type { QAAnswer, QAQueryInit, QAQuestion } from "./models/qa.ts";
+export { QACorpus, QAModel } from "./models/qa.ts";
+export type {
+  QAAnswer,
+  QAContextQueryInit,
+  QAContextQuestions,
+  QACorpusAnswer,
+  QACorpusQueryInit,
+  QAModelInit,
+  QAPassage,
+  QAQueryInit,
+  QAQuestion,
+} from "./models/qa.ts";
 
 export { NERModel } from "./models/ner.ts";
 
 export { Polarity, SentimentModel } from "./models/sentiment.ts";
-export type { Sentiment } from "./models/sentiment.ts";
+export type {
+  AspectSentiment,
+  AspectSentimentInit,
+  DocumentSentiment,
+  SentenceSentiment,
+  Sentiment,
+  TextSpan,
+} from "./models/sentiment.ts";
 
 export type {
   TranslateInit,
@@ -19,7 +44,11 @@ export { Language } from "./models/translation/language.ts";
 export { TranslationModel } from "./models/translation/mod.ts";
 
 export { POSModel } from "./models/pos.ts";
-export type { POSEntity } from "./models/pos.ts";
+export type {
+  POSEntity,
+  POSPredictOptions,
+  POSTagSet,
+} from "./models/pos.ts";
 
 export {
   Conversation,
@@ -29,9 +58,60 @@ export {
 export type { ConversationInit } from "./models/conversation.ts";
 
 export { ZeroShotClassificationModel } from "./models/zero_shot_classification.ts";
-export type { ZeroShotPredictInit } from "./models/zero_shot_classification.ts";
+export type {
+  LabelProbability,
+  LabelProbabilityTensor,
+  ZeroShotDistributionInit,
+  ZeroShotPredictInit,
+} from "./models/zero_shot_classification.ts";
 
 export type { Label } from "./models/sequence_classification.ts";
 
 export { TextGenerationModel } from "./models/text_generation.ts";
 export type { TextGenerationInit } from "./models/text_generation.ts";
+
+export { SentenceEmbeddingsModel } from "./models/sentence_embeddings.ts";
+export type {
+  Pooling,
+  SentenceEmbeddingsEncodeInit,
+  SentenceEmbeddingsModelInit,
+} from "./models/sentence_embeddings.ts";
+
+export { KeywordExtractionModel } from "./models/keyword_extraction.ts";
+export type {
+  Keyword,
+  KeywordExtractionModelInit,
+  KeywordScorer,
+} from "./models/keyword_extraction.ts";
+
+export { FillMaskModel } from "./models/fill_mask.ts";
+export type {
+  BertModelInit,
+  FillMaskInit,
+  MaskCandidate,
+  MaskCandidateTensors,
+} from "./models/fill_mask.ts";
+
+export { EncoderModel } from "./models/encoder.ts";
+export type { EncoderInit, EncoderOutput } from "./models/encoder.ts";
+export type { ResultFormat, Tensor } from "./utils/tensor_frame.ts";
+
+export { Tokenizer } from "./models/tokenizer.ts";
+export type {
+  DecodeInit,
+  TokenizeInit,
+  Tokenized,
+  TokenizerInit,
+  TokenizerKind,
+} from "./models/tokenizer.ts";
+
+export { VectorIndex } from "./models/vector_index.ts";
+export type {
+  IndexKind,
+  Metric,
+  SearchResult,
+  SearchResultTensor,
+  VectorIndexInfo,
+  VectorIndexInit,
+  VectorIndexLoadInit,
+} from "./models/vector_index.ts";
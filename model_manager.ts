@@ -1,6 +1,6 @@
-import type { TypedDLOpenDynamicLib } from "https://deno.land/x/typedffi@v0.1.2/mod.ts";
 import type { Model } from "./model.ts";
 import { QAModel } from "./models/qa.ts";
+import type { QAModelInit } from "./models/qa.ts";
 import { NERModel } from "./models/ner.ts";
 import { SentimentModel } from "./models/sentiment.ts";
 import { TranslationModel } from "./models/translation/mod.ts";
@@ -10,125 +10,149 @@ import type { TranslationModelInit } from "./models/translation/mod.ts";
 import { POSModel } from "./models/pos.ts";
 import { TextGenerationModel } from "./models/text_generation.ts";
 import { SummarizationModel } from "./models/summarization.ts";
-import { encode } from "./utils/encode.ts";
+import { SentenceEmbeddingsModel } from "./models/sentence_embeddings.ts";
+import type { SentenceEmbeddingsModelInit } from "./models/sentence_embeddings.ts";
+import { KeywordExtractionModel } from "./models/keyword_extraction.ts";
+import type { KeywordExtractionModelInit } from "./models/keyword_extraction.ts";
+import { FillMaskModel } from "./models/fill_mask.ts";
+import type { BertModelInit } from "./models/fill_mask.ts";
+import { EncoderModel } from "./models/encoder.ts";
+import { Tokenizer } from "./models/tokenizer.ts";
+import type { TokenizerInit } from "./models/tokenizer.ts";
+import { VectorIndex } from "./models/vector_index.ts";
+import type {
+  VectorIndexInit,
+  VectorIndexLoadInit,
+} from "./models/vector_index.ts";
+import { codecIds, deserialize, serialize } from "./utils/codec.ts";
+import type { Codec } from "./utils/codec.ts";
 import { decode } from "./utils/decode.ts";
 import { BertMLError } from "./error.ts";
 import { Plug } from "https://deno.land/x/plug@0.4.1/mod.ts";
+import { symbolDefinitions } from "./symbols.ts";
+import type { FFISymbols } from "./symbols.ts";
 
-const symbolDefinitions = {
-  create_qa_model: { parameters: [], result: "isize", nonblocking: true },
-  qa_query: {
-    parameters: ["usize", "buffer", "usize", "usize", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_ner_model: { parameters: [], result: "isize", nonblocking: true },
-  ner_predict: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_sentiment_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  sentiment_predict: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_translation_model: {
-    parameters: ["buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  translation_translate: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_conversation_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_conversation_manager: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_conversation: {
-    parameters: ["usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  conversation_send: {
-    parameters: ["usize", "usize", "usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_pos_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  pos_predict: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_zero_shot_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  zero_shot_predict: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  zero_shot_predict_multilabel: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_text_generation_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  text_generation_generate: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  create_summarization_model: {
-    parameters: [],
-    result: "isize",
-    nonblocking: true,
-  },
-  summarization_summarize: {
-    parameters: ["usize", "buffer", "usize"],
-    result: "isize",
-    nonblocking: true,
-  },
-  error_len: { parameters: [], result: "usize", nonblocking: true },
-  fill_result: {
-    parameters: ["buffer", "usize"],
-    result: "void",
-    nonblocking: true,
-  },
-  fill_error: { parameters: ["buffer"], result: "void", nonblocking: true },
-  delete_model: { parameters: ["usize"], result: "isize", nonblocking: true },
-} as const;
-
-type FFISymbols = TypedDLOpenDynamicLib<typeof symbolDefinitions>["symbols"];
+/** The version of the C ABI (`BERTML_ABI_VERSION` in `include/bertml.h`) that the bindings use. */
+const ABI_VERSION = 2;
+
+export interface ModelManagerOptions {
+  /**
+   * The encoding of the inputs and results of the native functions, defaults
+   * to `"json"`. MessagePack and CBOR avoid JSON escaping for large batches.
+   */
+  codec?: Codec;
+}
+
+/** An allocated model or resource. */
+export interface AllocationInfo {
+  rid: number;
+  /** The kind of model or resource (e.g. `"SentimentModel"`). */
+  kind: string;
+}
+
+/**
+ * Describes the build of the library, the devices it can run on and what it
+ * has allocated.
+ */
+export interface LibraryInfo {
+  versions: {
+    bertml: string;
+    /** The version of the C ABI, see `bertml_abi_version`. */
+    abi: number;
+    /** Null when the versions could not be resolved during the build. */
+    rustBert: string | null;
+    tch: string | null;
+    /** Null when libtorch was not found through `LIBTORCH` during the build. */
+    libtorch: string | null;
+  };
+  /** The cargo features that the library was built with. */
+  features: string[];
+  /** The kinds of pipeline models that can be created. */
+  pipelines: string[];
+  /** The kinds of resources (e.g. vector indices) that can be created. */
+  resources: string[];
+  devices: {
+    cpu: {
+      /** The number of threads that libtorch uses within an operation. */
+      threads: number;
+      /** The number of threads that libtorch uses to run operations in parallel. */
+      interopThreads: number;
+      mkl: boolean;
+      openmp: boolean;
+    };
+    cuda: {
+      available: boolean;
+      devices: number;
+      cudnn: boolean;
+    };
+  };
+  models: AllocationInfo[];
+  modelResources: AllocationInfo[];
+}
+
+/** The size and usage of an allocated model. */
+export interface ModelStats {
+  rid: number;
+  kind: string;
+  /**
+   * Whether the model is loaded, or was evicted to stay within the memory
+   * budget and will be reloaded on its next use.
+   */
+  loaded: boolean;
+  /**
+   * The number of parameters, which is unknown for translation models since
+   * their weight files are hidden.
+   */
+  parameters: number | null;
+  /**
+   * The memory of the weights, which is the size of the weight files for the
+   * models whose weights the library doesn't own.
+   */
+  weightBytes: number | null;
+  /** How much the resident memory of the process grew while the model loaded. */
+  loadedBytes: number | null;
+  /**
+   * `weightBytes` when it is known and `loadedBytes` otherwise, which is what
+   * the memory budget counts.
+   */
+  memoryBytes: number | null;
+  /**
+   * The vocab size, which is unknown for translation models and for some kinds
+   * of tokenizers.
+   */
+  vocabSize: number | null;
+  /** Milliseconds since the Unix epoch. */
+  createdAt: number;
+  /** Milliseconds since the Unix epoch. */
+  lastUsedAt: number;
+  /**
+   * The number of inference calls that used the model, which doesn't count
+   * lookups like tokenizing or getting the max length.
+   */
+  calls: number;
+  /** The number of times that the model was evicted. */
+  evictions: number;
+}
+
+/** The stats of every allocated model and their totals. */
+export interface ModelStatsReport {
+  models: ModelStats[];
+  total: {
+    models: number;
+    loaded: number;
+    parameters: number;
+    memoryBytes: number;
+    calls: number;
+  };
+  /** See `ModelManager.setModelMemoryBudget`. */
+  memoryBudget: number | null;
+}
 
 /** Provides an abstraction for creating models that run on the same native thread (but don't block the JS thread). */
 export class ModelManager {
   #symbols: FFISymbols;
   #close: () => void;
+  #codec: Codec;
   #models: Model[] = [];
   #isClosed = false;
 
@@ -141,7 +165,7 @@ export class ModelManager {
       const { bindings } = this;
       const len = await bindings.error_len();
       const buf = new Uint8Array(len);
-      await bindings.fill_error(buf);
+      await bindings.fill_error(buf, len);
       throw new BertMLError(decode(buf));
     }
     return code;
@@ -166,18 +190,36 @@ export class ModelManager {
     getResultString: async (len: number): Promise<string> => {
       return decode(await this.helpers.getResult(len));
     },
+    /** Decodes the result with the codec of the manager. */
+    getResultValue: async <T>(len: number): Promise<T> => {
+      return deserialize<T>(this.#codec, await this.helpers.getResult(len));
+    },
+    /** Encodes an input with the codec of the manager. */
+    serialize: (value: unknown): Uint8Array => {
+      return serialize(this.#codec, value);
+    },
   } as const;
 
   get helpers() {
     return this.#helpers;
   }
 
-  constructor(lib: Deno.DynamicLibrary<any>) {
+  /** The encoding of the inputs and results of the native functions. */
+  get codec(): Codec {
+    return this.#codec;
+  }
+
+  constructor(lib: Deno.DynamicLibrary<any>, codec: Codec = "json") {
     this.#symbols = lib.symbols as any;
     this.#close = lib.close.bind(lib);
+    this.#codec = codec;
   }
 
-  static async create(): Promise<ModelManager> {
+  /**
+   * Loads the library and sets its codec. The codec applies to the whole
+   * library, so every manager of the same library should use the same codec.
+   */
+  static async create(options: ModelManagerOptions = {}): Promise<ModelManager> {
     const lib = await Plug.prepare(
       {
         name: "bertml",
@@ -185,11 +227,25 @@ export class ModelManager {
       },
       symbolDefinitions as any
     );
-    return new ModelManager(lib);
+    const manager = new ModelManager(lib, options.codec);
+    const abiVersion = await manager.bindings.bertml_abi_version();
+    if (abiVersion !== ABI_VERSION) {
+      manager.#close();
+      throw new BertMLError(
+        `Expected version ${ABI_VERSION} of the bertml ABI but the library has version ${abiVersion}.`
+      );
+    }
+    await manager.bindings
+      .set_codec(codecIds[manager.codec])
+      .then(manager.assertCode);
+    return manager;
   }
 
-  async createQAModel(): Promise<QAModel> {
-    const rid = await this.bindings.create_qa_model().then(this.assertCode);
+  async createQAModel(init: QAModelInit = {}): Promise<QAModel> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_qa_model(bytes, bytes.length)
+      .then(this.assertCode);
     const model = new QAModel(this, rid);
     this.#models.push(model);
     return model;
@@ -214,7 +270,7 @@ export class ModelManager {
   async createTranslationModel<T extends TranslationModelInit>(
     init: T
   ): Promise<TranslationModel<T>> {
-    const bytes = encode(JSON.stringify(init));
+    const bytes = this.helpers.serialize(init);
     const rid = await this.bindings
       .create_translation_model(bytes, bytes.length)
       .then(this.assertCode);
@@ -266,6 +322,104 @@ export class ModelManager {
     return model;
   }
 
+  async createSentenceEmbeddingsModel(
+    init: SentenceEmbeddingsModelInit = {}
+  ): Promise<SentenceEmbeddingsModel> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_sentence_embeddings_model(bytes, bytes.length)
+      .then(this.assertCode);
+    const model = new SentenceEmbeddingsModel(this, rid);
+    this.#models.push(model);
+    return model;
+  }
+
+  async createKeywordExtractionModel(
+    init: KeywordExtractionModelInit = {}
+  ): Promise<KeywordExtractionModel> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_keyword_extraction_model(bytes, bytes.length)
+      .then(this.assertCode);
+    const model = new KeywordExtractionModel(this, rid);
+    this.#models.push(model);
+    return model;
+  }
+
+  async createFillMaskModel(init: BertModelInit = {}): Promise<FillMaskModel> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_fill_mask_model(bytes, bytes.length)
+      .then(this.assertCode);
+    const model = new FillMaskModel(this, rid);
+    this.#models.push(model);
+    return model;
+  }
+
+  async createEncoderModel(init: BertModelInit = {}): Promise<EncoderModel> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_encoder_model(bytes, bytes.length)
+      .then(this.assertCode);
+    const model = new EncoderModel(this, rid);
+    this.#models.push(model);
+    return model;
+  }
+
+  /** Loads a tokenizer from vocab files without a model. */
+  async createTokenizer(init: TokenizerInit): Promise<Tokenizer> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_tokenizer(bytes, bytes.length)
+      .then(this.assertCode);
+    const model = new Tokenizer(this, rid);
+    this.#models.push(model);
+    return model;
+  }
+
+  /** Creates an empty vector index in native memory. */
+  async createVectorIndex(init: VectorIndexInit): Promise<VectorIndex> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .create_vector_index(bytes, bytes.length)
+      .then(this.assertCode);
+    return new VectorIndex(this, rid);
+  }
+
+  /** Loads a vector index that was written by `VectorIndex.save`. */
+  async loadVectorIndex(init: VectorIndexLoadInit): Promise<VectorIndex> {
+    const bytes = this.helpers.serialize(init);
+    const rid = await this.bindings
+      .vector_index_load(bytes, bytes.length)
+      .then(this.assertCode);
+    return new VectorIndex(this, rid);
+  }
+
+  /**
+   * Gets the versions, features and devices of the library along with the
+   * models and resources that are allocated in it.
+   */
+  async info(): Promise<LibraryInfo> {
+    const len = await this.bindings.bertml_info().then(this.assertCode);
+    return JSON.parse(await this.helpers.getResultString(len));
+  }
+
+  /** Gets the memory, parameters and usage of every allocated model. */
+  async modelStats(): Promise<ModelStatsReport> {
+    const len = await this.bindings.get_model_stats().then(this.assertCode);
+    return this.helpers.getResultValue(len);
+  }
+
+  /**
+   * Limits the memory that the loaded models may take up, or removes the
+   * limit with `null`. When a model doesn't fit, the least recently used
+   * models are unloaded and then loaded again from their config the next time
+   * they are used.
+   */
+  async setModelMemoryBudget(bytes: number | null): Promise<void> {
+    await this.bindings.set_model_memory_budget(bytes ?? 0);
+  }
+
   close() {
     this.#close();
     this.#isClosed = true;
@@ -0,0 +1,72 @@
+import type { ModelManager } from "../model_manager.ts";
+import { Model } from "../model.ts";
+import { decode, maxLength, tokenize } from "./tokenizer.ts";
+import type { DecodeInit, TokenizeInit, Tokenized } from "./tokenizer.ts";
+import { decodeTensorFrame } from "../utils/tensor_frame.ts";
+import type { Tensor } from "../utils/tensor_frame.ts";
+
+export interface EncoderInit {
+  inputs: string[];
+  /**
+   * Whether to return the hidden states of every layer (including the
+   * embeddings), defaults to `false`.
+   */
+  allLayers?: boolean;
+  /** Whether to return the attention weights of every layer, defaults to `false`. */
+  attentions?: boolean;
+}
+
+/**
+ * The outputs of the encoder. The inputs are padded to the same number of
+ * tokens, so the rows after `tokenIds[i].length` of input `i` are padding.
+ */
+export interface EncoderOutput {
+  /** `[batch, tokens, hidden]` */
+  lastHiddenState: Tensor;
+  /** `[batch, hidden]` */
+  pooledOutput?: Tensor;
+  /** `[layers, batch, tokens, hidden]`, when `allLayers` is set. */
+  hiddenStates?: Tensor;
+  /** `[layers, batch, heads, tokens, tokens]`, when `attentions` is set. */
+  attentions?: Tensor;
+  /** The token ids of each input. */
+  tokenIds: number[][];
+}
+
+/** A BERT encoder without a task head, for reading its hidden states and attentions. */
+export class EncoderModel extends Model {
+  constructor(manager: ModelManager, rid: number) {
+    super(manager, rid);
+  }
+
+  /** Runs the encoder on the inputs as one batch. */
+  async forward(init: EncoderInit): Promise<EncoderOutput> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(init);
+    const len = await bindings
+      .encoder_forward(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    const { tensors, metadata } = decodeTensorFrame<{ tokenIds: number[][] }>(
+      await helpers.getResult(len),
+    );
+    return {
+      lastHiddenState: tensors.lastHiddenState as Tensor,
+      pooledOutput: tensors.pooledOutput as Tensor | undefined,
+      hiddenStates: tensors.hiddenStates as Tensor | undefined,
+      attentions: tensors.attentions as Tensor | undefined,
+      tokenIds: metadata.tokenIds,
+    };
+  }
+
+  tokenize(init: TokenizeInit): Promise<Tokenized[]> {
+    return tokenize(this, init);
+  }
+
+  decode(init: DecodeInit): Promise<string[]> {
+    return decode(this, init);
+  }
+
+  maxLength(): Promise<number> {
+    return maxLength(this);
+  }
+}
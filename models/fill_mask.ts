@@ -0,0 +1,96 @@
+import type { ModelManager } from "../model_manager.ts";
+import { Model } from "../model.ts";
+import { decode, maxLength, tokenize } from "./tokenizer.ts";
+import type { DecodeInit, TokenizeInit, Tokenized } from "./tokenizer.ts";
+import { decodeTensorFrame } from "../utils/tensor_frame.ts";
+import type { Tensor } from "../utils/tensor_frame.ts";
+
+/** The configuration of a model that is loaded from a BERT checkpoint. */
+export interface BertModelInit {
+  /**
+   * A directory with a `config.json`, `vocab.txt` and `rust_model.ot`.
+   * Defaults to `bert-base-uncased` from the Hugging Face hub.
+   */
+  modelPath?: string;
+  /** Whether the tokenizer lowercases the input, defaults to `true`. */
+  lowerCase?: boolean;
+}
+
+export interface FillMaskInit {
+  /** The inputs with one or more masks each. */
+  inputs: string[];
+  /** The string that marks the masks in the inputs, defaults to `"[MASK]"`. */
+  maskToken?: string;
+  /** The number of candidates for each mask, defaults to `5`. */
+  topK?: number;
+}
+
+/** A token that could fill a mask. */
+export interface MaskCandidate {
+  /** The id of the token in the vocabulary. */
+  id: number;
+  text: string;
+  /** The probability of the token at the mask. */
+  score: number;
+}
+
+/** The candidates of the masks of all inputs as tensors. */
+export interface MaskCandidateTensors {
+  /** The token ids, `[masks, topK]`. */
+  ids: Tensor<BigInt64Array>;
+  /** The probabilities of the tokens, `[masks, topK]`. */
+  scores: Tensor;
+  /** The number of masks of each input, `[inputs]`. */
+  maskCounts: Tensor<BigInt64Array>;
+}
+
+/** A model for predicting the most probable tokens for masked words. */
+export class FillMaskModel extends Model {
+  constructor(manager: ModelManager, rid: number) {
+    super(manager, rid);
+  }
+
+  /**
+   * Predicts the candidates for each mask of each input, in the order of the
+   * masks in the input. Throws when a mask is past the max length of the model.
+   */
+  async predict(init: FillMaskInit): Promise<MaskCandidate[][][]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(init);
+    const len = await bindings
+      .fill_mask_predict(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Same as `predict` but returns the ids and scores of the candidates of the
+   * masks of all inputs as tensors.
+   */
+  async predictTensor(init: FillMaskInit): Promise<MaskCandidateTensors> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize({ ...init, format: "tensor" });
+    const len = await bindings
+      .fill_mask_predict(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    const { tensors } = decodeTensorFrame(await helpers.getResult(len));
+    return {
+      ids: tensors.ids as Tensor<BigInt64Array>,
+      scores: tensors.scores as Tensor,
+      maskCounts: tensors.maskCounts as Tensor<BigInt64Array>,
+    };
+  }
+
+  /** Tokenizes the inputs with the tokenizer of the model. */
+  tokenize(init: TokenizeInit): Promise<Tokenized[]> {
+    return tokenize(this, init);
+  }
+
+  decode(init: DecodeInit): Promise<string[]> {
+    return decode(this, init);
+  }
+
+  maxLength(): Promise<number> {
+    return maxLength(this);
+  }
+}
@@ -0,0 +1,65 @@
+import type { ModelManager } from "../model_manager.ts";
+import { Model } from "../model.ts";
+import type { TextSpan } from "./sentiment.ts";
+
+/**
+ * How the keyphrases are ranked.
+ *
+ * - `"cosine"`: by the similarity of the keyphrase to the document.
+ * - `"mmr"`: by Maximal Marginal Relevance, which trades similarity to the
+ *   document for diversity.
+ * - `"maxSum"`: by the least similar combination of the candidates that are the
+ *   most similar to the document.
+ */
+export type KeywordScorer = "cosine" | "mmr" | "maxSum";
+
+/** The configuration of a keyword extraction model. */
+export interface KeywordExtractionModelInit {
+  /**
+   * The directory of a local SBERT-style model. Defaults to
+   * `all-MiniLM-L6-v2` from the Hugging Face hub.
+   */
+  modelPath?: string;
+  /** The smallest and largest number of words in a keyphrase, defaults to `[1, 1]`. */
+  ngramRange?: [number, number];
+  /**
+   * Replaces the default English stopwords. Keyphrases do not start or end
+   * with stopwords.
+   */
+  stopwords?: string[];
+  /** The maximum number of keyphrases for each input, defaults to `5`. */
+  maxKeywords?: number;
+  /** Defaults to `"cosine"`. */
+  scorer?: KeywordScorer;
+  /** The diversity of `"mmr"` between 0 and 1, defaults to `0.5`. */
+  diversity?: number;
+  /** The number of candidates that `"maxSum"` combines, defaults to `20`. */
+  maxSumCandidates?: number;
+}
+
+/** A keyphrase of an input. */
+export interface Keyword {
+  /** The keyphrase (lowercased). */
+  text: string;
+  /** The similarity of the keyphrase to the input. */
+  score: number;
+  /** Every occurrence of the keyphrase in the input. */
+  occurrences: TextSpan[];
+}
+
+/** A model for extracting the keyphrases of documents. */
+export class KeywordExtractionModel extends Model {
+  constructor(manager: ModelManager, rid: number) {
+    super(manager, rid);
+  }
+
+  /** Extracts the ranked keyphrases of each of the inputs. */
+  async extract(inputs: string[]): Promise<Keyword[][]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(inputs);
+    const len = await bindings
+      .keywords_extract(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+}
@@ -1,6 +1,5 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
 import { decode } from "../utils/decode.ts";
 
 interface NEREntity {
@@ -16,10 +15,10 @@ export class NERModel extends Model {
 
   async predict(text: string[]): Promise<NEREntity[][]> {
     const { bindings, assertCode, helpers } = this.manager;
-    const bytes = encode(JSON.stringify(text));
+    const bytes = helpers.serialize(text);
     const len = await bindings
       .ner_predict(this.rid, bytes, bytes.length)
       .then(assertCode);
-    return JSON.parse(await helpers.getResultString(len));
+    return await helpers.getResultValue(len);
   }
 }
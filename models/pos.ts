@@ -1,15 +1,45 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
 
-/** An entity that describes the part of speech of a word. */
+/**
+ * The tag set of the labels.
+ *
+ * - `"ptb"`: the Penn Treebank tags that the model predicts (e.g. `NNS`, `VBZ`).
+ * - `"ud"`: the Universal Dependencies part of speech tags (e.g. `NOUN`,
+ *   `VERB`), mapped from the Penn Treebank tags. Tags without a mapping become
+ *   `X`.
+ */
+export type POSTagSet = "ptb" | "ud";
+
+/**
+ * An entity that describes the part of speech of a word.
+ *
+ * This is the stable output schema of `POSModel.predict`:
+ *
+ * ```json
+ * { "word": "cat", "score": 0.99, "label": "NN", "start": 4, "end": 7 }
+ * ```
+ */
 export interface POSEntity {
-  /** The given word. */
+  /** The given word (lowercased by uncased models). */
   word: string;
   /** The confidence score. */
   score: number;
   /** The label that defines the part of speech of the word. */
   label: string;
+  /**
+   * The start of the word in the input (as a JavaScript string index), `null`
+   * if the word could not be found in the input (e.g. when the tokenizer strips
+   * accents).
+   */
+  start: number | null;
+  /** The end of the word in the input, `null` when `start` is. */
+  end: number | null;
+}
+
+export interface POSPredictOptions {
+  /** The tag set of the labels, defaults to `"ptb"`. */
+  tagSet?: POSTagSet;
 }
 
 /** A model for finding the part of speech of words in a centain. */
@@ -18,13 +48,16 @@ export class POSModel extends Model {
     super(manager, rid);
   }
 
-  /** Predicts the parts of speech for the words in the given inputs. */
-  async predict(inputs: string[]): Promise<POSEntity[]> {
+  /** Predicts the parts of speech for the words in each of the given inputs. */
+  async predict(
+    inputs: string[],
+    options: POSPredictOptions = {},
+  ): Promise<POSEntity[][]> {
     const { bindings, assertCode, helpers } = this.manager;
-    const bytes = encode(JSON.stringify(inputs));
+    const bytes = helpers.serialize({ inputs, ...options });
     const len = await bindings
       .pos_predict(this.rid, bytes, bytes.length)
       .then(assertCode);
-    return JSON.parse(await helpers.getResultString(len));
+    return await helpers.getResultValue(len);
   }
 }
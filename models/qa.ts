@@ -1,6 +1,7 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
+import { decode, maxLength, tokenize } from "./tokenizer.ts";
+import type { DecodeInit, TokenizeInit, Tokenized } from "./tokenizer.ts";
 
 export interface QAQuestion {
   /** The context to find the answer within. */
@@ -9,15 +10,41 @@ export interface QAQuestion {
   question: string;
 }
 
+/** The configuration of a question answering model. */
+export interface QAModelInit {
+  /** The maximum length of a window of the context and question, defaults to '384'. */
+  maxSeqLength?: number;
+  /** The overlap between the windows of long contexts, defaults to '128'. */
+  docStride?: number;
+  /** The maximum length of the question, defaults to '64'. */
+  maxQueryLength?: number;
+  /** The maximum length of an answer in tokens, defaults to '15'. */
+  maxAnswerLength?: number;
+}
+
+export interface QAContextQuestions {
+  /** The context to find the answers within. */
+  context: string;
+  /** The questions to try to solve. */
+  questions: string[];
+}
+
 export interface QAAnswer {
   /** The confidence score. */
   score: number;
-  /** The start in the input of the answer. */
+  /** The start of the answer in the context (as a JavaScript string index). */
   start: number;
-  /** The end of the input of the answer. */
+  /** The end of the answer in the context (as a JavaScript string index). */
   end: number;
   /** The answer text. */
   answer: string;
+  /**
+   * The score of the null answer minus the score of this answer, where the
+   * scores are the sums of the start and end logits. A question has no answers
+   * when this is higher than `noAnswerThreshold` for its best answer, so it can
+   * be used to pick a threshold.
+   */
+  noAnswerScore: number;
 }
 
 /** A query for the prediction. */
@@ -28,6 +55,107 @@ export interface QAQueryInit {
   nTopAnswers?: number;
   /** The maxmimum batch size for the model forward pass. */
   batchSize?: number;
+  /**
+   * Lets SQuAD2-style models answer that the context has no answer, which is
+   * an empty array of answers. A question has no answer when the score of the
+   * null answer is higher than the score of the best answer by more than this,
+   * where the scores are the sums of the start and end logits. By default every
+   * question is answered.
+   */
+  noAnswerThreshold?: number;
+}
+
+/** A query for the prediction where each context has many questions. */
+export interface QAContextQueryInit {
+  /** The contexts and the questions to ask about them. */
+  contexts: QAContextQuestions[];
+  /** The number of top answers to include (e.g. 1 will include top result). */
+  nTopAnswers?: number;
+  /** The maxmimum batch size for the model forward pass. */
+  batchSize?: number;
+  /** See `QAQueryInit.noAnswerThreshold`. */
+  noAnswerThreshold?: number;
+}
+
+/** A passage that can be retrieved from a `QACorpus`. */
+export interface QAPassage {
+  /** The id that is included with the answers found in this passage. */
+  id: string;
+  /** The text of the passage. */
+  text: string;
+}
+
+export interface QACorpusQueryInit {
+  /** The questions to find the answers for in the corpus. */
+  questions: string[];
+  /** The number of passages retrieved for each question, defaults to '3'. */
+  nPassages?: number;
+  /** The number of top answers to include (e.g. 1 will include top result). */
+  nTopAnswers?: number;
+  /** The maxmimum batch size for the model forward pass. */
+  batchSize?: number;
+  /** See `QAQueryInit.noAnswerThreshold`. */
+  noAnswerThreshold?: number;
+}
+
+export interface QACorpusAnswer extends QAAnswer {
+  /** The id of the passage that the answer was found in. */
+  passageId: string;
+  /** The BM25 score of the passage for the question. */
+  passageScore: number;
+}
+
+/**
+ * A set of passages stored in native memory. Questions are only answered with
+ * the passages that match them the best, so the corpus can be much larger than
+ * what the model can take in at once.
+ */
+export class QACorpus {
+  #model: QAModel;
+  #rid: number;
+
+  get rid(): number {
+    return this.#rid;
+  }
+
+  get model(): QAModel {
+    return this.#model;
+  }
+
+  constructor(model: QAModel, rid: number) {
+    this.#model = model;
+    this.#rid = rid;
+  }
+
+  /**
+   * Retrieves the passages that match each question the best with BM25 and
+   * returns the top answers found in those passages.
+   */
+  async query(init: QACorpusQueryInit): Promise<QACorpusAnswer[][]> {
+    const {
+      questions,
+      nPassages = 3,
+      nTopAnswers = 1,
+      batchSize = 32,
+      noAnswerThreshold,
+    } = init;
+    const { bindings, assertCode, helpers } = this.model.manager;
+    const bytes = helpers.serialize({
+      questions,
+      nPassages,
+      nTopAnswers,
+      batchSize,
+      noAnswerThreshold: noAnswerThreshold ?? null,
+    });
+    const len = await bindings
+      .qa_corpus_query(this.model.rid, this.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  close() {
+    this.model.manager.bindings.delete_model_resource(this.#rid);
+  }
 }
 
 /** A model used for finding the answer within a context for inputs. */
@@ -38,14 +166,67 @@ export class QAModel extends Model {
 
   /** Creates a query into the model with the given question(s) and returns the results with the n top answers. */
   async query(init: QAQueryInit): Promise<QAAnswer[][]> {
-    const { questionGroups, nTopAnswers = 1, batchSize = 32 } = init;
+    const {
+      questionGroups,
+      nTopAnswers = 1,
+      batchSize = 32,
+      noAnswerThreshold,
+    } = init;
     const { bindings, assertCode, helpers } = this.manager;
 
-    const s = JSON.stringify(questionGroups);
-    const bytes = encode(s);
+    const bytes = helpers.serialize({
+      questionGroups,
+      noAnswerThreshold: noAnswerThreshold ?? null,
+    });
     const len = await bindings
-      .qa_query(this.rid, bytes, s.length, nTopAnswers, batchSize)
+      .qa_query(this.rid, bytes, bytes.length, nTopAnswers, batchSize)
       .then(assertCode);
-    return JSON.parse(await helpers.getResultString(len));
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Same as `query` except that each context is only sent once for all of its
+   * questions. The answers are grouped by context and then by question.
+   */
+  async queryContexts(init: QAContextQueryInit): Promise<QAAnswer[][][]> {
+    const {
+      contexts,
+      nTopAnswers = 1,
+      batchSize = 32,
+      noAnswerThreshold,
+    } = init;
+    const { bindings, assertCode, helpers } = this.manager;
+
+    const bytes = helpers.serialize({
+      contexts,
+      noAnswerThreshold: noAnswerThreshold ?? null,
+    });
+    const len = await bindings
+      .qa_query_contexts(this.rid, bytes, bytes.length, nTopAnswers, batchSize)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /** Stores the given passages in native memory to ask questions about. */
+  async createCorpus(passages: QAPassage[]): Promise<QACorpus> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(passages);
+    const rid = await bindings
+      .create_qa_corpus(bytes, bytes.length)
+      .then(assertCode);
+    return new QACorpus(this, rid);
+  }
+
+  /** Tokenizes the inputs with the tokenizer of the model. */
+  tokenize(init: TokenizeInit): Promise<Tokenized[]> {
+    return tokenize(this, init);
+  }
+
+  decode(init: DecodeInit): Promise<string[]> {
+    return decode(this, init);
+  }
+
+  maxLength(): Promise<number> {
+    return maxLength(this);
   }
 }
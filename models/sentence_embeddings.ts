@@ -0,0 +1,49 @@
+import type { ModelManager } from "../model_manager.ts";
+import { Model } from "../model.ts";
+
+/** How the token embeddings are pooled into one sentence embedding. */
+export type Pooling = "mean" | "cls" | "max" | "meanSqrtLen";
+
+/** The configuration of a sentence embeddings model. */
+export interface SentenceEmbeddingsModelInit {
+  /**
+   * The directory of a local SBERT-style model (e.g. a clone of
+   * `sentence-transformers/all-MiniLM-L6-v2` with a `rust_model.ot`). Defaults
+   * to `all-MiniLM-L12-v2` from the Hugging Face hub.
+   */
+  modelPath?: string;
+  /** Overrides the pooling in the model's `1_Pooling/config.json`. */
+  pooling?: Pooling;
+}
+
+export interface SentenceEmbeddingsEncodeInit {
+  /** The sentences to encode. */
+  inputs: string[];
+  /** Whether the embeddings should be scaled to a length of 1. */
+  normalize?: boolean;
+}
+
+/** A model for encoding sentences into vectors. */
+export class SentenceEmbeddingsModel extends Model {
+  constructor(manager: ModelManager, rid: number) {
+    super(manager, rid);
+  }
+
+  /**
+   * Encodes each of the inputs into an embedding. The embeddings are views
+   * into one buffer that holds all of them.
+   */
+  async encode(init: SentenceEmbeddingsEncodeInit): Promise<Float32Array[]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(init);
+    const len = await bindings
+      .sentence_embeddings_encode(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    const buf = await helpers.getResult(len);
+    const data = new Float32Array(buf.buffer, buf.byteOffset, len / 4);
+    const dimension = data.length / Math.max(init.inputs.length, 1);
+    return init.inputs.map((_, i) =>
+      data.subarray(i * dimension, (i + 1) * dimension)
+    );
+  }
+}
@@ -1,6 +1,6 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
+import type { POSModel } from "./pos.ts";
 
 /** Describes the spectrum that the sentiment points towards. */
 export enum Polarity {
@@ -16,6 +16,78 @@ export interface Sentiment {
   score: number;
 }
 
+/** A slice of an input. The offsets are JavaScript string indices. */
+export interface TextSpan {
+  /** The text of the span. */
+  text: string;
+  /** The start of the span in the input. */
+  start: number;
+  /** The end of the span in the input. */
+  end: number;
+}
+
+/** Describes the sentiment of a sentence within a document. */
+export interface SentenceSentiment extends Sentiment, TextSpan {}
+
+/** Describes the sentiment of a document from the sentiments of its sentences. */
+export interface DocumentSentiment {
+  /** The sentiments of each sentence in the document. */
+  sentences: SentenceSentiment[];
+  /**
+   * The mean sentence score, where the scores of negative sentences are
+   * negated (-1 to 1).
+   */
+  mean: number;
+  /** Same as `mean` but each sentence is weighted by its length. */
+  weightedMean: number;
+  /** The index of the most negative sentence, `null` when no sentence is negative. */
+  mostNegative: number | null;
+}
+
+export interface AspectSentimentInit {
+  /** The texts to find the aspect sentiments of. */
+  inputs: string[];
+  /** The aspects to look for in each input (e.g. `["battery", "screen"]`). */
+  aspects?: string[];
+  /**
+   * A POS model used to extract noun phrases as the aspects when `aspects` is
+   * not given.
+   */
+  posModel?: POSModel;
+  /** Only used by zero shot models, defaults to '128'. */
+  maxLength?: number;
+}
+
+/** Describes the sentiment towards an aspect of an input. */
+export interface AspectSentiment {
+  /** The aspect that the sentiment is about. */
+  aspect: string;
+  /** The polarity of the sentiment towards the aspect. */
+  polarity: Polarity;
+  /** The confidence score of the polarity. */
+  score: number;
+  /** The sentence that supports the polarity the most. */
+  sentence: TextSpan;
+}
+
+/**
+ * Predicts the sentiment towards each aspect mentioned in the inputs with a
+ * sentiment or zero shot classification model. Aspects that are not mentioned
+ * in an input are left out of its results.
+ */
+export async function predictAspectSentiments(
+  model: Model,
+  init: AspectSentimentInit,
+): Promise<AspectSentiment[][]> {
+  const { bindings, assertCode, helpers } = model.manager;
+  const { posModel, ...rest } = init;
+  const bytes = helpers.serialize({ ...rest, posModel: posModel?.rid });
+  const len = await bindings
+    .aspect_sentiment_predict(model.rid, bytes, bytes.length)
+    .then(assertCode);
+  return await helpers.getResultValue(len);
+}
+
 /** A model used to calculate the given sentiment of inputs. */
 export class SentimentModel extends Model {
   constructor(manager: ModelManager, rid: number) {
@@ -25,10 +97,28 @@ export class SentimentModel extends Model {
   /** Predicts the sentiments of the given inputs. */
   async predict(input: string[]): Promise<Sentiment[]> {
     const { bindings, assertCode, helpers } = this.manager;
-    const bytes = encode(JSON.stringify(input));
+    const bytes = helpers.serialize(input);
     const len = await bindings
       .sentiment_predict(this.rid, bytes, bytes.length)
       .then(assertCode);
-    return JSON.parse(await helpers.getResultString(len));
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Splits each of the documents into sentences and predicts the sentiment of
+   * each sentence along with aggregate statistics for the whole document.
+   */
+  async predictDocuments(documents: string[]): Promise<DocumentSentiment[]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(documents);
+    const len = await bindings
+      .sentiment_predict_document(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /** Predicts the sentiment towards each aspect mentioned in the inputs. */
+  predictAspects(init: AspectSentimentInit): Promise<AspectSentiment[][]> {
+    return predictAspectSentiments(this, init);
   }
 }
@@ -1,6 +1,5 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
 
 export interface TextGenerationInit {
   /** The inputs to generate text for. */
@@ -18,11 +17,10 @@ export class TextGenerationModel extends Model {
   /** Generates more text from a shorter piece of text. */
   async generate(init: TextGenerationInit): Promise<string[]> {
     const { bindings, helpers, assertCode } = this.manager;
-    const bytes = encode(JSON.stringify(init));
+    const bytes = helpers.serialize(init);
     const len = await bindings
       .text_generation_generate(this.rid, bytes, bytes.length)
       .then(assertCode);
-    const labels = await helpers.getResultString(len);
-    return JSON.parse(labels);
+    return await helpers.getResultValue(len);
   }
 }
@@ -0,0 +1,125 @@
+import type { ModelManager } from "../model_manager.ts";
+import { Model } from "../model.ts";
+import type { EncoderModel } from "./encoder.ts";
+import type { FillMaskModel } from "./fill_mask.ts";
+import type { QAModel } from "./qa.ts";
+
+/** The kind of tokenizer to load from vocab files. */
+export type TokenizerKind =
+  | "bert"
+  | "distilBert"
+  | "roberta"
+  | "gpt2"
+  | "albert"
+  | "xlmRoberta";
+
+/** The configuration of a tokenizer without a model. */
+export interface TokenizerInit {
+  kind: TokenizerKind;
+  /** The vocab file (e.g. `vocab.txt` or `vocab.json`). */
+  vocabPath: string;
+  /** The merges file of BPE tokenizers (e.g. `merges.txt`). */
+  mergesPath?: string;
+  /** Defaults to `false`. */
+  lowerCase?: boolean;
+  stripAccents?: boolean;
+  addPrefixSpace?: boolean;
+  /** The maximum number of tokens of an input, defaults to `512`. */
+  maxLength?: number;
+}
+
+export interface TokenizeInit {
+  inputs: string[];
+  /**
+   * Whether to add the special tokens of the model (e.g. `[CLS]` and
+   * `[SEP]`), defaults to `true`.
+   */
+  addSpecialTokens?: boolean;
+  /** Truncates the inputs to this many tokens, defaults to the max length. */
+  maxLength?: number;
+}
+
+/** The tokens of an input. */
+export interface Tokenized {
+  ids: number[];
+  tokens: string[];
+  /**
+   * The start and end of each token in the input (as JavaScript string
+   * indices), `null` for special tokens.
+   */
+  offsets: ([number, number] | null)[];
+  /** `1` for special tokens and `0` for the tokens of the input. */
+  specialTokensMask: number[];
+  /** The number of tokens that were cut off by truncation. */
+  numTruncated: number;
+}
+
+export interface DecodeInit {
+  ids: number[][];
+  /** Defaults to `true`. */
+  skipSpecialTokens?: boolean;
+  /** Defaults to `true`. */
+  cleanUpTokenizationSpaces?: boolean;
+}
+
+/**
+ * The models that expose their tokenizer. The rust-bert pipelines (e.g.
+ * `NERModel` or `SentimentModel`) don't, create a `Tokenizer` from the vocab
+ * files of their model instead.
+ */
+export type TokenizerModel =
+  | Tokenizer
+  | FillMaskModel
+  | EncoderModel
+  | QAModel;
+
+/** Tokenizes the inputs with the tokenizer of the model. */
+export async function tokenize(
+  model: TokenizerModel,
+  init: TokenizeInit,
+): Promise<Tokenized[]> {
+  const { bindings, assertCode, helpers } = model.manager;
+  const bytes = helpers.serialize(init);
+  const len = await bindings
+    .tokenizer_encode(model.rid, bytes, bytes.length)
+    .then(assertCode);
+  return await helpers.getResultValue(len);
+}
+
+/** Decodes each list of ids back into text. */
+export async function decode(
+  model: TokenizerModel,
+  init: DecodeInit,
+): Promise<string[]> {
+  const { bindings, assertCode, helpers } = model.manager;
+  const bytes = helpers.serialize(init);
+  const len = await bindings
+    .tokenizer_decode(model.rid, bytes, bytes.length)
+    .then(assertCode);
+  return await helpers.getResultValue(len);
+}
+
+/** Gets the maximum number of tokens that the model accepts. */
+export async function maxLength(model: TokenizerModel): Promise<number> {
+  const { bindings, assertCode } = model.manager;
+  return await bindings.tokenizer_max_length(model.rid).then(assertCode);
+}
+
+/** A tokenizer that is loaded from vocab files without a model. */
+export class Tokenizer extends Model {
+  constructor(manager: ModelManager, rid: number) {
+    super(manager, rid);
+  }
+
+  tokenize(init: TokenizeInit): Promise<Tokenized[]> {
+    return tokenize(this, init);
+  }
+
+  decode(init: DecodeInit): Promise<string[]> {
+    return decode(this, init);
+  }
+
+  maxLength(): Promise<number> {
+    return maxLength(this);
+  }
+}
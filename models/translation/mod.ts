@@ -1,7 +1,6 @@
 import type { ModelManager } from "../../model_manager.ts";
 import type { Language } from "./language.ts";
 import { Model } from "../../model.ts";
-import { encode } from "../../utils/encode.ts";
 
 export interface TranslationModelInit<
   SourceLanguages extends Language[] = Language[],
@@ -36,10 +35,10 @@ export class TranslationModel<T extends TranslationModelInit> extends Model {
    */
   async translate(init: TranslateInit<T>): Promise<string[]> {
     const { bindings, assertCode, helpers } = this.manager;
-    const bytes = encode(JSON.stringify(init));
+    const bytes = helpers.serialize(init);
     const len = await bindings
       .translation_translate(this.rid, bytes, bytes.length)
       .then(assertCode);
-    return JSON.parse(await helpers.getResultString(len));
+    return await helpers.getResultValue(len);
   }
 }
@@ -0,0 +1,218 @@
+import type { ModelManager } from "../model_manager.ts";
+import type { SentenceEmbeddingsModel } from "./sentence_embeddings.ts";
+import { encode } from "../utils/encode.ts";
+import { decodeTensorFrame } from "../utils/tensor_frame.ts";
+import type { Tensor } from "../utils/tensor_frame.ts";
+
+/**
+ * How the similarity of vectors is measured. Cosine indexes normalize the
+ * vectors so that the scores are cosine similarities.
+ */
+export type Metric = "cosine" | "dot";
+
+/**
+ * The kind of search that the index uses. Exact indexes compare the query to
+ * every vector while HNSW indexes search an approximate graph which is much
+ * faster for large indexes.
+ */
+export type IndexKind = "exact" | "hnsw";
+
+/** The configuration of a vector index. */
+export interface VectorIndexInit {
+  /** The dimension of the vectors. */
+  dimension: number;
+  /** Defaults to `"cosine"`. */
+  metric?: Metric;
+  /** Defaults to `"exact"`. */
+  kind?: IndexKind;
+  /** The number of neighbors of each HNSW node, defaults to '16'. */
+  m?: number;
+  /** The size of the HNSW candidate list when adding, defaults to '200'. */
+  efConstruction?: number;
+  /** The size of the HNSW candidate list when searching, defaults to '64'. */
+  efSearch?: number;
+  /**
+   * The id of the model that the vectors are created with, which is saved
+   * with the index.
+   */
+  modelId?: string;
+}
+
+/** How to load a saved vector index. */
+export interface VectorIndexLoadInit {
+  /** The path of the file written by `VectorIndex.save`. */
+  path: string;
+  /**
+   * Whether to read the vectors from a memory map of the file instead of
+   * copying them into memory, defaults to `false`.
+   */
+  mmap?: boolean;
+}
+
+/** The configuration and size of a vector index. */
+export interface VectorIndexInfo {
+  dimension: number;
+  metric: Metric;
+  kind: IndexKind;
+  modelId: string;
+  /** The number of vectors in the index. */
+  len: number;
+}
+
+/** A vector found by a search. */
+export interface SearchResult {
+  /** The id of the vector. */
+  id: string;
+  /** The similarity of the vector to the query. */
+  score: number;
+}
+
+/** The results of searches as a tensor of scores. */
+export interface SearchResultTensor {
+  /** `[queries, k]`, padded with `NaN` when a query has fewer results. */
+  scores: Tensor;
+  /** The ids of the results of each query, in the order of the scores. */
+  ids: string[][];
+}
+
+const toBytes = (vectors: Float32Array[]): Uint8Array => {
+  const data = new Float32Array(
+    vectors.reduce((len, vector) => len + vector.length, 0),
+  );
+  let offset = 0;
+  for (const vector of vectors) {
+    data.set(vector, offset);
+    offset += vector.length;
+  }
+  return new Uint8Array(data.buffer);
+};
+
+/** An index of vectors stored in native memory that can be searched by similarity. */
+export class VectorIndex {
+  #manager: ModelManager;
+  #rid: number;
+
+  get rid(): number {
+    return this.#rid;
+  }
+
+  get manager(): ModelManager {
+    return this.#manager;
+  }
+
+  constructor(manager: ModelManager, rid: number) {
+    this.#manager = manager;
+    this.#rid = rid;
+  }
+
+  /**
+   * Adds the vectors with the given ids, replacing the vectors that already
+   * have the ids. Returns the number of vectors in the index.
+   */
+  async add(ids: string[], vectors: Float32Array[]): Promise<number> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const idBytes = helpers.serialize(ids);
+    const vectorBytes = toBytes(vectors);
+    return await bindings
+      .vector_index_add(
+        this.rid,
+        idBytes,
+        idBytes.length,
+        vectorBytes,
+        vectorBytes.length,
+      )
+      .then(assertCode);
+  }
+
+  /**
+   * Encodes the texts with the model and adds them with the given ids. Returns
+   * the number of vectors in the index.
+   */
+  async addTexts(
+    model: SentenceEmbeddingsModel,
+    ids: string[],
+    texts: string[],
+  ): Promise<number> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize({ ids, texts });
+    return await bindings
+      .vector_index_add_texts(this.rid, model.rid, bytes, bytes.length)
+      .then(assertCode);
+  }
+
+  /**
+   * Removes the vectors with the ids and returns the number that were removed.
+   * The space of removed vectors is not reclaimed, also not when saving.
+   */
+  async remove(ids: string[]): Promise<number> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize(ids);
+    return await bindings
+      .vector_index_remove(this.rid, bytes, bytes.length)
+      .then(assertCode);
+  }
+
+  /** Finds the `k` most similar vectors to each of the queries. */
+  async search(queries: Float32Array[], k: number): Promise<SearchResult[][]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = toBytes(queries);
+    const len = await bindings
+      .vector_index_search(this.rid, bytes, bytes.length, k)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /** Encodes the queries with the model and finds the `k` most similar vectors to each. */
+  async searchTexts(
+    model: SentenceEmbeddingsModel,
+    queries: string[],
+    k: number,
+  ): Promise<SearchResult[][]> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize({ queries, k });
+    const len = await bindings
+      .vector_index_search_texts(this.rid, model.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Writes the index to the file at the path so that it can be loaded with
+   * `ModelManager.loadVectorIndex`.
+   */
+  async save(path: string): Promise<void> {
+    const { bindings, assertCode } = this.manager;
+    const bytes = encode(path);
+    await bindings
+      .vector_index_save(this.rid, bytes, bytes.length)
+      .then(assertCode);
+  }
+
+  /** Gets the configuration and size of the index. */
+  async info(): Promise<VectorIndexInfo> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const len = await bindings.vector_index_info(this.rid).then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /** Same as `searchTexts` but returns the scores as a tensor. */
+  async searchTextsTensor(
+    model: SentenceEmbeddingsModel,
+    queries: string[],
+    k: number,
+  ): Promise<SearchResultTensor> {
+    const { bindings, assertCode, helpers } = this.manager;
+    const bytes = helpers.serialize({ queries, k, format: "tensor" });
+    const len = await bindings
+      .vector_index_search_texts(this.rid, model.rid, bytes, bytes.length)
+      .then(assertCode);
+    const { tensors, metadata } = decodeTensorFrame<{ ids: string[][] }>(
+      await helpers.getResult(len),
+    );
+    return { scores: tensors.scores as Tensor, ids: metadata.ids };
+  }
+
+  close() {
+    this.manager.bindings.delete_model_resource(this.#rid);
+  }
+}
@@ -1,13 +1,59 @@
 import type { ModelManager } from "../model_manager.ts";
 import { Model } from "../model.ts";
-import { encode } from "../utils/encode.ts";
+import { decodeTensorFrame } from "../utils/tensor_frame.ts";
+import type { Tensor } from "../utils/tensor_frame.ts";
 import type { Label } from "./sequence_classification.ts";
+import { predictAspectSentiments } from "./sentiment.ts";
+import type { AspectSentiment, AspectSentimentInit } from "./sentiment.ts";
 
 export interface ZeroShotPredictInit {
   inputs: string[];
   labels: string[];
   /** defaults to '128' */
   maxLength?: number;
+  /**
+   * The hypothesis for each label where `{}` is replaced by the label (e.g.
+   * `"This message expresses {}."`), defaults to `"This example is {}."`.
+   */
+  template?: string;
+  /** Templates for specific labels that take priority over `template`. */
+  labelTemplates?: Record<string, string>;
+  /**
+   * Text that is put into the hypothesis instead of the label itself (e.g.
+   * `{ billing: "a question about an invoice or payment" }`). The results still
+   * use the label.
+   */
+  labelDescriptions?: Record<string, string>;
+}
+
+export interface ZeroShotDistributionInit extends ZeroShotPredictInit {
+  /**
+   * Whether more than one label can be true for an input. Multi-label
+   * probabilities are independent of each other while single-label
+   * probabilities add up to 1. Defaults to `false`.
+   */
+  multiLabel?: boolean;
+  /** Labels with a probability below this are left out. */
+  threshold?: number;
+  /** The maximum number of labels to return for each input. */
+  topK?: number;
+}
+
+/** The probability of a label for an input. */
+export interface LabelProbability {
+  /** The label. */
+  text: string;
+  /** The index of the label. */
+  id: number;
+  /** The probability that the label is true for the input. */
+  probability: number;
+}
+
+/** The probabilities of every label for every input as one tensor. */
+export interface LabelProbabilityTensor {
+  /** `[inputs, labels]`, in the order of the labels. */
+  probabilities: Tensor;
+  labels: string[];
 }
 
 /**
@@ -26,23 +72,67 @@ export class ZeroShotClassificationModel extends Model {
   async predict(init: ZeroShotPredictInit): Promise<Label[]> {
     init.maxLength ??= 128;
     const { bindings, helpers, assertCode } = this.manager;
-    const bytes = encode(JSON.stringify(init));
+    const bytes = helpers.serialize(init);
     const len = await bindings
       .zero_shot_predict(this.rid, bytes, bytes.length)
       .then(assertCode);
-    const labels = await helpers.getResultString(len);
-    return JSON.parse(labels);
+    return await helpers.getResultValue(len);
   }
 
   /** Predicts the score of each label for each of the inputs. */
   async predictMultilabel(init: ZeroShotPredictInit): Promise<Label[][]> {
     init.maxLength ??= 128;
     const { bindings, helpers, assertCode } = this.manager;
-    const bytes = encode(JSON.stringify(init));
+    const bytes = helpers.serialize(init);
     const len = await bindings
       .zero_shot_predict_multilabel(this.rid, bytes, bytes.length)
       .then(assertCode);
-    const labels = await helpers.getResultString(len);
-    return JSON.parse(labels);
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Predicts the probability of every label for each of the inputs, ranked
+   * from the most to the least probable label.
+   */
+  async predictDistribution(
+    init: ZeroShotDistributionInit,
+  ): Promise<LabelProbability[][]> {
+    init.maxLength ??= 128;
+    const { bindings, helpers, assertCode } = this.manager;
+    const bytes = helpers.serialize(init);
+    const len = await bindings
+      .zero_shot_predict_distribution(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    return await helpers.getResultValue(len);
+  }
+
+  /**
+   * Same as `predictDistribution` but returns the probabilities of every
+   * label as a tensor, without the `threshold` and `topK`.
+   */
+  async predictDistributionTensor(
+    init: ZeroShotDistributionInit,
+  ): Promise<LabelProbabilityTensor> {
+    init.maxLength ??= 128;
+    const { bindings, helpers, assertCode } = this.manager;
+    const bytes = helpers.serialize({ ...init, format: "tensor" });
+    const len = await bindings
+      .zero_shot_predict_distribution(this.rid, bytes, bytes.length)
+      .then(assertCode);
+    const { tensors, metadata } = decodeTensorFrame<{ labels: string[] }>(
+      await helpers.getResult(len),
+    );
+    return {
+      probabilities: tensors.probabilities as Tensor,
+      labels: metadata.labels,
+    };
+  }
+
+  /**
+   * Predicts the sentiment towards each aspect mentioned in the inputs by
+   * classifying hypotheses like "The battery is positive.".
+   */
+  predictAspects(init: AspectSentimentInit): Promise<AspectSentiment[][]> {
+    return predictAspectSentiments(this, init);
   }
 }
@@ -1,12 +1,22 @@
-use crate::create_allocator;
+use crate::{
+    create_allocator, vocab_size, EncoderModel, FillMaskModel, PassageCorpus, QAModel,
+    StandaloneTokenizer, VectorIndex, WeightStats,
+};
+use anyhow::Context;
+use rust_bert::pipelines::common::{ModelType, TokenizerOption};
 use rust_bert::pipelines::{
-    conversation, ner, pos_tagging, question_answering, sentiment, summarization, text_generation,
-    translation, zero_shot_classification,
+    conversation, keywords_extraction, ner, pos_tagging, sentence_embeddings, sentiment,
+    summarization, text_generation, translation, zero_shot_classification,
 };
+use rust_bert::resources::ResourceProvider;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
 
 pub enum Model {
     TranslationModel(translation::TranslationModel),
-    QuestionAnsweringModel(question_answering::QuestionAnsweringModel),
+    QuestionAnsweringModel(QAModel),
     NERModel(ner::NERModel),
     SentimentModel(sentiment::SentimentModel),
     ConversationModel(conversation::ConversationModel),
@@ -14,10 +24,93 @@ pub enum Model {
     ZeroShotClassificationModel(zero_shot_classification::ZeroShotClassificationModel),
     TextGenerationModel(text_generation::TextGenerationModel),
     SummarizationModel(summarization::SummarizationModel),
+    SentenceEmbeddingsModel(sentence_embeddings::SentenceEmbeddingsModel),
+    KeywordExtractionModel(keywords_extraction::KeywordExtractionModel<'static>),
+    FillMaskModel(FillMaskModel),
+    Tokenizer(StandaloneTokenizer),
+    EncoderModel(EncoderModel),
+}
+
+impl Model {
+    /// The kinds of models that can be allocated, as named by `kind`.
+    pub const KINDS: &'static [&'static str] = &[
+        "TranslationModel",
+        "QuestionAnsweringModel",
+        "NERModel",
+        "SentimentModel",
+        "ConversationModel",
+        "POSModel",
+        "ZeroShotClassificationModel",
+        "TextGenerationModel",
+        "SummarizationModel",
+        "SentenceEmbeddingsModel",
+        "KeywordExtractionModel",
+        "FillMaskModel",
+        "Tokenizer",
+        "EncoderModel",
+    ];
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Model::TranslationModel(_) => "TranslationModel",
+            Model::QuestionAnsweringModel(_) => "QuestionAnsweringModel",
+            Model::NERModel(_) => "NERModel",
+            Model::SentimentModel(_) => "SentimentModel",
+            Model::ConversationModel(_) => "ConversationModel",
+            Model::POSModel(_) => "POSModel",
+            Model::ZeroShotClassificationModel(_) => "ZeroShotClassificationModel",
+            Model::TextGenerationModel(_) => "TextGenerationModel",
+            Model::SummarizationModel(_) => "SummarizationModel",
+            Model::SentenceEmbeddingsModel(_) => "SentenceEmbeddingsModel",
+            Model::KeywordExtractionModel(_) => "KeywordExtractionModel",
+            Model::FillMaskModel(_) => "FillMaskModel",
+            Model::Tokenizer(_) => "Tokenizer",
+            Model::EncoderModel(_) => "EncoderModel",
+        }
+    }
+
+    /// The size of the weights of the models whose var store bertml owns. The weights of the
+    /// `rust-bert` pipelines are private to them, see `record_weights`.
+    pub fn weights(&self) -> Option<WeightStats> {
+        match self {
+            Model::QuestionAnsweringModel(model) => Some(model.weights()),
+            Model::FillMaskModel(model) => Some(model.weights()),
+            Model::EncoderModel(model) => Some(model.weights()),
+            _ => None,
+        }
+    }
+
+    /// The vocab size of the models whose tokenizer bertml owns. The tokenizers of the
+    /// `rust-bert` pipelines are private to them, see `record_vocab`.
+    pub fn vocab_size(&self) -> Option<usize> {
+        match self {
+            Model::QuestionAnsweringModel(model) => vocab_size(model.tokenizer()),
+            Model::FillMaskModel(model) => vocab_size(model.tokenizer()),
+            Model::EncoderModel(model) => vocab_size(model.tokenizer()),
+            Model::Tokenizer(tokenizer) => vocab_size(tokenizer.tokenizer()),
+            _ => None,
+        }
+    }
 }
 
 pub enum ModelResource {
     ConversationManager(conversation::ConversationManager),
+    PassageCorpus(PassageCorpus),
+    VectorIndex(VectorIndex),
+}
+
+impl ModelResource {
+    /// The kinds of resources that can be allocated, as named by `kind`.
+    pub const KINDS: &'static [&'static str] =
+        &["ConversationManager", "PassageCorpus", "VectorIndex"];
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ModelResource::ConversationManager(_) => "ConversationManager",
+            ModelResource::PassageCorpus(_) => "PassageCorpus",
+            ModelResource::VectorIndex(_) => "VectorIndex",
+        }
+    }
 }
 
 /// For models that are required to use a model resource but cannot be a model resource due to
@@ -26,6 +119,322 @@ pub enum ModelResourceAccessor {
     ConversationId(uuid::Uuid),
 }
 
+/// An item that can be allocated.
+pub trait Allocatable {
+    fn kind(&self) -> &'static str;
+
+    /// The memory of the weights of the item when bertml owns them, otherwise the allocators use
+    /// the size of the weight files that were recorded with `record_weights` while it loaded.
+    fn memory_bytes(&self) -> Option<u64> {
+        None
+    }
+
+    /// The number of parameters of the item when bertml owns its weights, otherwise the
+    /// allocators use the parameters that were recorded with `record_weights` while it loaded.
+    fn parameters(&self) -> Option<u64> {
+        None
+    }
+
+    /// The vocab size of the item when bertml owns its tokenizer, otherwise the allocators use
+    /// the vocab size that was recorded with `record_vocab` while it loaded.
+    fn vocab_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl Allocatable for Model {
+    fn kind(&self) -> &'static str {
+        Model::kind(self)
+    }
+
+    fn memory_bytes(&self) -> Option<u64> {
+        self.weights().map(|weights| weights.bytes)
+    }
+
+    fn parameters(&self) -> Option<u64> {
+        self.weights().map(|weights| weights.parameters)
+    }
+
+    fn vocab_size(&self) -> Option<usize> {
+        Model::vocab_size(self)
+    }
+}
+
+impl Allocatable for ModelResource {
+    fn kind(&self) -> &'static str {
+        ModelResource::kind(self)
+    }
+}
+
+impl Allocatable for ModelResourceAccessor {
+    fn kind(&self) -> &'static str {
+        match self {
+            ModelResourceAccessor::ConversationId(_) => "ConversationId",
+        }
+    }
+}
+
+/// Loads an item again from the config it was first loaded with.
+pub type Reload<T> = Box<dyn Fn() -> anyhow::Result<T> + Send>;
+
+/// What the item that is loading recorded with `record_weights` and `record_vocab`.
+#[derive(Clone, Copy, Default)]
+struct Recorded {
+    weight_file_bytes: Option<u64>,
+    parameters: Option<u64>,
+    vocab_size: Option<usize>,
+}
+
+thread_local! {
+    /// What the item that is loading on this thread recorded.
+    static RECORDED: Cell<Recorded> = Cell::new(Recorded::default());
+}
+
+/// Records that the item that is loading reads its weights from the resource, for the
+/// `rust-bert` pipelines whose var store is private to them. The tensors of a checkpoint take up
+/// about as much memory as its file, so the size of the file is used as their memory and models
+/// are evicted to make room for it before it is read. The parameters are counted by reading the
+/// tensors of the checkpoint, which are dropped again.
+pub fn record_weights(resource: &dyn ResourceProvider) -> anyhow::Result<()> {
+    let path = resource
+        .get_local_path()
+        .context("Failed to get model weights.")?;
+    let bytes = std::fs::metadata(&path)
+        .context("Failed to read model weights.")?
+        .len();
+    // Room is made for the earlier weight files of the item too, which stay loaded
+    let recorded_bytes = RECORDED.with(|recorded| recorded.get().weight_file_bytes);
+    models::make_room(recorded_bytes.unwrap_or(0) + bytes);
+    let parameters = tch::Tensor::load_multi(&path)
+        .context("Failed to read model weights.")?
+        .iter()
+        .map(|(_, tensor)| tensor.numel() as u64)
+        .sum::<u64>();
+    RECORDED.with(|recorded| {
+        let mut total = recorded.get();
+        total.weight_file_bytes = Some(total.weight_file_bytes.unwrap_or(0) + bytes);
+        total.parameters = Some(total.parameters.unwrap_or(0) + parameters);
+        recorded.set(total);
+    });
+    Ok(())
+}
+
+/// Evicts models to make room for the weight file at the path before it is loaded, using its
+/// size as the memory of its tensors, and returns the size.
+pub fn reserve_weights(path: &Path) -> anyhow::Result<u64> {
+    let bytes = std::fs::metadata(path)
+        .context("Failed to read model weights.")?
+        .len();
+    models::make_room(bytes);
+    Ok(bytes)
+}
+
+/// Records the vocab size of the tokenizer that the item that is loading builds from the files,
+/// for the `rust-bert` pipelines whose tokenizer is private to them. The tokenizer is built from
+/// the same files to count its vocab and is dropped again.
+pub fn record_vocab(
+    model_type: ModelType,
+    vocab: &dyn ResourceProvider,
+    merges: Option<&(dyn ResourceProvider + Send)>,
+) -> anyhow::Result<()> {
+    let vocab = vocab
+        .get_local_path()
+        .context("Failed to get tokenizer vocab.")?;
+    let merges = merges
+        .map(|merges| merges.get_local_path())
+        .transpose()
+        .context("Failed to get tokenizer merges.")?;
+    // The casing options of the tokenizer don't change its vocab
+    let tokenizer = TokenizerOption::from_file(
+        model_type,
+        vocab.to_str().context("Invalid tokenizer vocab path.")?,
+        merges
+            .as_deref()
+            .map(|merges| merges.to_str().context("Invalid tokenizer merges path."))
+            .transpose()?,
+        false,
+        None,
+        None,
+    )
+    .context("Failed to load tokenizer.")?;
+    let vocab_size = vocab_size(&tokenizer);
+    RECORDED.with(|recorded| {
+        let mut total = recorded.get();
+        total.vocab_size = vocab_size;
+        recorded.set(total);
+    });
+    Ok(())
+}
+
+/// An item that was just loaded, with what was measured while it loaded.
+pub struct Loaded<T> {
+    pub item: T,
+    /// How much the resident memory of the process grew while the item loaded.
+    pub loaded_bytes: Option<u64>,
+    /// The size of the weight files that were recorded with `record_weights`.
+    pub weight_file_bytes: Option<u64>,
+    /// The parameters that were recorded with `record_weights`.
+    pub parameters: Option<u64>,
+    /// The vocab size that was recorded with `record_vocab`.
+    pub vocab_size: Option<usize>,
+}
+
+impl<T> Loaded<T> {
+    /// An item that was loaded without being measured.
+    pub fn unmeasured(item: T) -> Self {
+        Self {
+            item,
+            loaded_bytes: None,
+            weight_file_bytes: None,
+            parameters: None,
+            vocab_size: None,
+        }
+    }
+
+    /// Loads the item with `load`. The caller must make sure that nothing else loads at the same
+    /// time so that the resident memory of other loads isn't attributed to it.
+    pub fn measure(load: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<Self> {
+        RECORDED.with(Cell::take);
+        let before = resident_memory();
+        let item = load();
+        let recorded = RECORDED.with(Cell::take);
+        Ok(Self {
+            item: item?,
+            loaded_bytes: before
+                .zip(resident_memory())
+                .map(|(before, after)| after.saturating_sub(before)),
+            weight_file_bytes: recorded.weight_file_bytes,
+            parameters: recorded.parameters,
+            vocab_size: recorded.vocab_size,
+        })
+    }
+}
+
+/// An allocated item along with how it has been used.
+pub struct Allocation<T> {
+    /// The item, or `None` while it is evicted.
+    pub item: Option<T>,
+    pub kind: &'static str,
+    /// The memory of the weights of the item: `Allocatable::memory_bytes` when it is known and
+    /// the size of its recorded weight files otherwise. It is updated when the item is reloaded.
+    pub weight_bytes: Option<u64>,
+    /// `weight_bytes` when it is known and `loaded_bytes` otherwise, which is what the memory
+    /// budget counts.
+    pub memory_bytes: Option<u64>,
+    /// `Allocatable::parameters` when it is known and the recorded parameters otherwise, kept
+    /// while the item is evicted.
+    pub parameters: Option<u64>,
+    /// `Allocatable::vocab_size` when it is known and the recorded vocab size otherwise, kept
+    /// while the item is evicted.
+    pub vocab_size: Option<usize>,
+    pub created_at: SystemTime,
+    pub last_used_at: SystemTime,
+    /// The number of calls that ran the item, which are the accesses with `with_access`.
+    pub calls: u64,
+    /// How much the resident memory of the process grew while the item was last loaded, if it
+    /// was loaded with `load` on a platform where the resident memory is known. It is only a
+    /// fallback for the memory budget, since it also counts the memory that other allocations
+    /// reuse.
+    pub loaded_bytes: Option<u64>,
+    /// The number of times that the item was evicted to stay within the memory budget.
+    pub evictions: u64,
+    /// Only items that can be reloaded are evicted.
+    pub(crate) reload: Option<Reload<T>>,
+}
+
+impl<T: Allocatable> Allocation<T> {
+    pub fn new(loaded: Loaded<T>, reload: Option<Reload<T>>) -> Self {
+        let now = SystemTime::now();
+        let mut allocation = Self {
+            kind: loaded.item.kind(),
+            item: None,
+            weight_bytes: None,
+            memory_bytes: None,
+            parameters: None,
+            vocab_size: None,
+            created_at: now,
+            last_used_at: now,
+            calls: 0,
+            loaded_bytes: None,
+            evictions: 0,
+            reload,
+        };
+        allocation.set_loaded(loaded);
+        allocation
+    }
+
+    /// Puts the loaded item into the allocation and updates its memory.
+    pub fn set_loaded(&mut self, loaded: Loaded<T>) {
+        self.weight_bytes = loaded.item.memory_bytes().or(loaded.weight_file_bytes);
+        self.memory_bytes = self.weight_bytes.or(loaded.loaded_bytes);
+        self.loaded_bytes = loaded.loaded_bytes;
+        self.parameters = loaded.item.parameters().or(loaded.parameters);
+        self.vocab_size = loaded.item.vocab_size().or(loaded.vocab_size);
+        self.item = Some(loaded.item);
+    }
+}
+
+impl<T> Allocation<T> {
+    pub fn is_loaded(&self) -> bool {
+        self.item.is_some()
+    }
+
+    /// The memory that the item takes up while it is loaded.
+    fn resident_bytes(&self) -> u64 {
+        if self.is_loaded() {
+            self.memory_bytes.unwrap_or(0)
+        } else {
+            0
+        }
+    }
+}
+
+/// Evicts the least recently used items, other than the item at `keep`, until `needed` more
+/// bytes fit in the budget or there are no items left that can be reloaded.
+pub fn evict<T>(
+    allocations: &mut HashMap<usize, Allocation<T>>,
+    budget: Option<u64>,
+    needed: u64,
+    keep: usize,
+) {
+    let budget = match budget {
+        Some(budget) => budget,
+        None => return,
+    };
+    let mut used = allocations
+        .values()
+        .map(Allocation::resident_bytes)
+        .sum::<u64>();
+    while used + needed > budget {
+        let lru = allocations
+            .iter_mut()
+            .filter(|(rid, allocation)| {
+                **rid != keep && allocation.resident_bytes() > 0 && allocation.reload.is_some()
+            })
+            .min_by_key(|(_, allocation)| allocation.last_used_at);
+        match lru {
+            Some((_, allocation)) => {
+                used -= allocation.resident_bytes();
+                allocation.item = None;
+                allocation.evictions += 1;
+            }
+            None => break,
+        }
+    }
+}
+
+/// Gets the resident memory of the process in bytes.
+pub fn resident_memory() -> Option<u64> {
+    if cfg!(target_os = "linux") {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+        Some(kb * 1024)
+    } else {
+        None
+    }
+}
+
 create_allocator! { pub alloc models for super::Model }
 create_allocator! { pub alloc model_resources for super::ModelResource }
 create_allocator! { pub alloc model_resource_accessors for super::ModelResourceAccessor }
@@ -40,34 +449,183 @@ mod macros {
             use std::sync::Mutex;
             use anyhow::Context;
 
-            pub static ALLOCATOR: Lazy<Mutex<HashMap<usize, $itm>>> =
+            pub static ALLOCATOR: Lazy<Mutex<HashMap<usize, $crate::Allocation<$itm>>>> =
                 Lazy::new(|| Mutex::new(HashMap::new()));
             pub static COUNTER: Lazy<Mutex<usize>> = Lazy::new(|| Mutex::new(0));
+            /// Held while an item is loaded so that the memory of loads on other threads isn't
+            /// attributed to it.
+            static LOADING: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+            /// The bytes that the loaded items may take up, see `set_memory_budget`.
+            static MEMORY_BUDGET: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
 
-            pub fn allocate(itm: $itm) -> Result<usize, anyhow::Error> {
+            /// The memory that items took up the last time they were loaded from each config,
+            /// keyed by the type of their load function and the config.
+            static CONFIG_MEMORY: Lazy<Mutex<HashMap<(&'static str, Vec<u8>), u64>>> =
+                Lazy::new(|| Mutex::new(HashMap::new()));
+
+            /// Allocates the item, evicting other items if it doesn't fit in the budget.
+            fn insert(allocation: $crate::Allocation<$itm>) -> usize {
+                let budget = memory_budget();
                 let mut counter = COUNTER.lock().unwrap();
                 let mut allocator = ALLOCATOR.lock().unwrap();
                 let rid = *counter;
-                allocator.insert(rid, itm);
+                allocator.insert(rid, allocation);
                 *counter += 1;
-                Ok(rid)
+                $crate::evict(&mut allocator, budget, 0, rid);
+                rid
+            }
+
+            pub fn allocate(itm: $itm) -> Result<usize, anyhow::Error> {
+                Ok(insert($crate::Allocation::new($crate::Loaded::unmeasured(itm), None)))
+            }
+
+            /// Loads the item with `load` and allocates it. When an item was loaded from the same
+            /// config before, the least recently used items are evicted to make room for the
+            /// memory it took up before it is loaded again. Otherwise items that load weight
+            /// files make room for them with `reserve_weights` (or `record_weights`) before they
+            /// read them, and the others are only measured once they are loaded, after which the
+            /// least recently used items are evicted until it fits. The item can be evicted to
+            /// stay within the memory budget, in which case it is loaded with `load` again on its
+            /// next access.
+            pub fn load<F>(config: &[u8], load: F) -> Result<usize, anyhow::Error>
+            where
+                F: Fn() -> Result<$itm, anyhow::Error> + Send + 'static,
+            {
+                let key = (std::any::type_name::<F>(), config.to_vec());
+                let _loading = LOADING.lock().unwrap();
+                let needed = CONFIG_MEMORY.lock().unwrap().get(&key).copied();
+                if let Some(needed) = needed {
+                    let budget = memory_budget();
+                    $crate::evict(&mut ALLOCATOR.lock().unwrap(), budget, needed, usize::MAX);
+                }
+
+                let allocation =
+                    $crate::Allocation::new($crate::Loaded::measure(&load)?, Some(Box::new(load)));
+                if let Some(bytes) = allocation.memory_bytes {
+                    CONFIG_MEMORY.lock().unwrap().insert(key, bytes);
+                }
+                Ok(insert(allocation))
             }
 
-            pub fn deallocate(rid: usize) -> Result<$itm, anyhow::Error> {
+            /// Evicts the least recently used items until `needed` more bytes fit in the budget,
+            /// for an item that is loading and isn't allocated yet.
+            pub fn make_room(needed: u64) {
+                let budget = memory_budget();
+                $crate::evict(&mut ALLOCATOR.lock().unwrap(), budget, needed, usize::MAX);
+            }
+
+            pub fn deallocate(rid: usize) -> Result<(), anyhow::Error> {
+                let mut allocator = ALLOCATOR.lock().unwrap();
+                allocator
+                    .remove(&rid)
+                    .map(|_| ())
+                    .with_context(|| format!("Failed to deallocate item with resource id of {}.", rid))
+            }
+
+            pub fn memory_budget() -> Option<u64> {
+                *MEMORY_BUDGET.lock().unwrap()
+            }
+
+            /// Sets the bytes that the loaded items may take up, or removes the budget with
+            /// `None`, and evicts the least recently used items that no longer fit.
+            pub fn set_memory_budget(budget: Option<u64>) {
+                *MEMORY_BUDGET.lock().unwrap() = budget;
                 let mut allocator = ALLOCATOR.lock().unwrap();
-                allocator.remove(&rid).with_context(|| format!("Failed to deallocate item with resource id of {}.", rid))
+                $crate::evict(&mut allocator, budget, 0, usize::MAX);
+            }
+
+            /// Maps every allocation, in the order of their rids.
+            pub fn map_all<T, F>(mut f: F) -> Vec<T>
+            where
+                F: FnMut(usize, &$crate::Allocation<$itm>) -> T,
+            {
+                let allocator = ALLOCATOR.lock().unwrap();
+                let mut rids = allocator.keys().copied().collect::<Vec<_>>();
+                rids.sort_unstable();
+                rids.into_iter().map(|rid| f(rid, &allocator[&rid])).collect()
             }
 
+            /// Runs `f` with the item, reloading it first if it was evicted. It counts as a call
+            /// of the item, so it is for the calls that run the item (e.g. inference).
             pub fn with_access<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
             where
                 F: FnOnce(&mut $itm) -> Result<T, anyhow::Error>,
+            {
+                access(rid, true, f)
+            }
 
+            /// Same as `with_access` for lookups that don't run the item (e.g. getting its
+            /// tokenizer), which aren't counted as calls.
+            pub fn with_lookup<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+            where
+                F: FnOnce(&mut $itm) -> Result<T, anyhow::Error>,
             {
+                access(rid, false, f)
+            }
+
+            /// Reloads the evicted item at the rid. The allocator is only locked to take out the
+            /// reload function and to put the item back, so that the other items can be used
+            /// while it loads.
+            fn reload(rid: usize) -> Result<(), anyhow::Error> {
+                // Loads take `LOADING` before the allocator
+                let _loading = LOADING.lock().unwrap();
+                let budget = memory_budget();
+                let reload = {
+                    let mut allocator = ALLOCATOR.lock().unwrap();
+                    let allocation = allocator
+                        .get_mut(&rid)
+                        .context("Failed to get resource with id.")?;
+                    // Another thread reloaded it while this one waited to load
+                    if allocation.is_loaded() {
+                        return Ok(());
+                    }
+                    let needed = allocation.memory_bytes.unwrap_or(0);
+                    let reload = allocation
+                        .reload
+                        .take()
+                        .context("Failed to reload evicted resource.")?;
+                    $crate::evict(&mut allocator, budget, needed, rid);
+                    reload
+                };
+
+                let loaded = $crate::Loaded::measure(&reload);
                 let mut allocator = ALLOCATOR.lock().unwrap();
-                match allocator.get_mut(&rid) {
-                    Some(guard) => f(guard),
-                    None => Err(anyhow::anyhow!("Failed to get resource with id.")),
+                let allocation = allocator
+                    .get_mut(&rid)
+                    .context("Resource was deallocated while it reloaded.")?;
+                allocation.reload = Some(reload);
+                allocation.set_loaded(loaded.with_context(|| {
+                    format!("Failed to reload item with resource id of {}.", rid)
+                })?);
+                // So that it isn't the first to be evicted before the call that reloaded it
+                allocation.last_used_at = std::time::SystemTime::now();
+                // The reloaded item can take up more memory than it did before
+                $crate::evict(&mut allocator, budget, 0, rid);
+                Ok(())
+            }
+
+            fn access<T, F>(rid: usize, call: bool, f: F) -> Result<T, anyhow::Error>
+            where
+                F: FnOnce(&mut $itm) -> Result<T, anyhow::Error>,
+            {
+                let mut allocator = loop {
+                    let allocator = ALLOCATOR.lock().unwrap();
+                    match allocator.get(&rid) {
+                        Some(allocation) if allocation.is_loaded() => break allocator,
+                        Some(_) => {
+                            drop(allocator);
+                            reload(rid)?;
+                        }
+                        None => return Err(anyhow::anyhow!("Failed to get resource with id.")),
+                    }
+                };
+
+                let allocation = allocator.get_mut(&rid).unwrap();
+                allocation.last_used_at = std::time::SystemTime::now();
+                if call {
+                    allocation.calls += 1;
                 }
+                f(allocation.item.as_mut().unwrap())
             }
         }
     };
@@ -0,0 +1,258 @@
+use crate::text::{self, TextSpan};
+use crate::{codec, exec, ffi, models, set_result, Model};
+use anyhow::Context;
+use rust_bert::pipelines::pos_tagging::POSModel;
+use rust_bert::pipelines::sentiment::{SentimentModel, SentimentPolarity};
+use rust_bert::pipelines::zero_shot_classification::ZeroShotClassificationModel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AspectSentimentInit {
+    pub inputs: Vec<String>,
+    /// The aspects to look for. When omitted, the noun phrases found by `pos_model` are used.
+    pub aspects: Option<Vec<String>>,
+    /// The rid of a POS model used to extract noun phrases as aspects.
+    pub pos_model: Option<usize>,
+    /// The maximum sequence length used when the polarity is found with a zero shot model.
+    #[serde(default = "default_max_length")]
+    pub max_length: usize,
+}
+
+fn default_max_length() -> usize {
+    128
+}
+
+#[derive(Debug, Serialize)]
+pub struct AspectSentiment {
+    pub aspect: String,
+    pub polarity: u8,
+    pub score: f64,
+    /// The sentence that supports the polarity the most.
+    pub sentence: TextSpan,
+}
+
+/// The model that finds the polarity of the sentences that mention an aspect.
+pub enum AspectClassifier<'a> {
+    Sentiment(&'a SentimentModel),
+    /// Asks the model whether "The {aspect} is positive." or negative.
+    ZeroShot(&'a ZeroShotClassificationModel),
+}
+
+/// The polarity of one sentence that mentions an aspect.
+struct Mention {
+    sentence: usize,
+    positive: bool,
+    score: f64,
+}
+
+/// Extracts runs of adjectives followed by nouns from the POS tags of each input.
+pub fn extract_noun_phrases(model: &POSModel, inputs: &[String]) -> Vec<Vec<String>> {
+    model
+        .predict(inputs)
+        .into_iter()
+        .map(|tags| {
+            let mut phrases: Vec<String> = Vec::new();
+            let mut words: Vec<&str> = Vec::new();
+            let mut has_noun = false;
+
+            for tag in tags.iter().map(Some).chain(std::iter::once(None)) {
+                let label = tag.map(|t| t.label.as_str()).unwrap_or("");
+                if label.starts_with("NN") {
+                    words.push(&tag.unwrap().word);
+                    has_noun = true;
+                    continue;
+                }
+
+                if has_noun {
+                    let phrase = words.join(" ").to_lowercase();
+                    if !phrases.contains(&phrase) {
+                        phrases.push(phrase);
+                    }
+                    words.clear();
+                    has_noun = false;
+                }
+
+                if label.starts_with("JJ") {
+                    words.push(&tag.unwrap().word);
+                } else {
+                    words.clear();
+                }
+            }
+
+            phrases
+        })
+        .collect()
+}
+
+/// Finds the polarity of each of the `aspects` of each input, which must have one list of aspects
+/// per input. Aspects that are not mentioned in an input are left out of its output.
+pub fn predict_aspects(
+    classifier: AspectClassifier<'_>,
+    inputs: &[String],
+    aspects: &[Vec<String>],
+    max_length: usize,
+) -> Vec<Vec<AspectSentiment>> {
+    let sentences: Vec<Vec<(usize, usize)>> = inputs
+        .iter()
+        .map(|input| text::split_sentences(input))
+        .collect();
+
+    // (input, aspect, sentence) for every sentence that mentions an aspect
+    let mut targets: Vec<(usize, usize, usize)> = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        for (a, aspect) in aspects[i].iter().enumerate() {
+            for (s, &(start, end)) in sentences[i].iter().enumerate() {
+                if text::contains_phrase(&input[start..end], aspect) {
+                    targets.push((i, a, s));
+                }
+            }
+        }
+    }
+
+    // rust-bert can't predict an empty batch
+    if targets.is_empty() {
+        return inputs.iter().map(|_| Vec::new()).collect();
+    }
+
+    let sentence_text = |&(i, _, s): &(usize, usize, usize)| {
+        let (start, end) = sentences[i][s];
+        &inputs[i][start..end]
+    };
+
+    let polarities: Vec<(bool, f64)> = match classifier {
+        AspectClassifier::Sentiment(m) => {
+            let texts = targets.iter().map(sentence_text).collect::<Vec<_>>();
+            m.predict(texts.as_slice())
+                .into_iter()
+                .map(|s| (!matches!(s.polarity, SentimentPolarity::Negative), s.score))
+                .collect()
+        }
+        AspectClassifier::ZeroShot(m) => {
+            // rust-bert pairs every input of a batch with the same hypotheses, so the mentions
+            // of each aspect are sent in one batch
+            let mut by_aspect: HashMap<&str, Vec<usize>> = HashMap::new();
+            for (t, &(i, a, _)) in targets.iter().enumerate() {
+                by_aspect.entry(&aspects[i][a]).or_default().push(t);
+            }
+
+            let mut polarities = vec![(false, 0.0); targets.len()];
+            for (aspect, group) in by_aspect {
+                let texts = group
+                    .iter()
+                    .map(|&t| sentence_text(&targets[t]))
+                    .collect::<Vec<_>>();
+                let aspect = aspect.to_string();
+                let labels = m.predict(
+                    texts.as_slice(),
+                    &["positive", "negative"],
+                    Some(Box::new(move |label: &str| {
+                        format!("The {} is {}.", aspect, label)
+                    })),
+                    max_length,
+                );
+                for (&t, label) in group.iter().zip(labels) {
+                    polarities[t] = (label.text == "positive", label.score);
+                }
+            }
+            polarities
+        }
+    };
+
+    let mut mentions: Vec<Vec<Vec<Mention>>> = aspects
+        .iter()
+        .map(|a| a.iter().map(|_| Vec::new()).collect())
+        .collect();
+    for (&(i, a, s), (positive, score)) in targets.iter().zip(polarities) {
+        mentions[i][a].push(Mention {
+            sentence: s,
+            positive,
+            score,
+        });
+    }
+
+    mentions
+        .into_iter()
+        .enumerate()
+        .map(|(i, by_aspect)| {
+            by_aspect
+                .into_iter()
+                .enumerate()
+                .filter(|(_, mentions)| !mentions.is_empty())
+                .map(|(a, mentions)| {
+                    let signed: f64 = mentions
+                        .iter()
+                        .map(|m| if m.positive { m.score } else { -m.score })
+                        .sum();
+                    let positive = signed >= 0.0;
+                    let agreeing = mentions
+                        .iter()
+                        .filter(|m| m.positive == positive)
+                        .collect::<Vec<_>>();
+                    let score =
+                        agreeing.iter().map(|m| m.score).sum::<f64>() / agreeing.len() as f64;
+                    let support = agreeing
+                        .iter()
+                        .max_by(|a, b| a.score.total_cmp(&b.score))
+                        .unwrap();
+                    let (start, end) = sentences[i][support.sentence];
+
+                    AspectSentiment {
+                        aspect: aspects[i][a].clone(),
+                        polarity: positive as u8,
+                        score,
+                        sentence: TextSpan::from_byte_range(&inputs[i], start, end),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn aspect_sentiment_predict(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: AspectSentimentInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse aspect sentiment input.")?;
+
+        let aspects = match (init.aspects, init.pos_model) {
+            (Some(aspects), _) => vec![aspects; init.inputs.len()],
+            (None, Some(pos_rid)) => models::with_access(pos_rid, |model| match model {
+                Model::POSModel(m) => Ok(extract_noun_phrases(m, &init.inputs)),
+                _ => Err(anyhow::anyhow!("Expected POS Model at rid '{}'.", pos_rid)),
+            })?,
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "Expected either aspects or a POS model to extract aspects with."
+                ))
+            }
+        };
+
+        let outputs = models::with_access(rid, |model| {
+            let classifier = match model {
+                Model::SentimentModel(m) => AspectClassifier::Sentiment(m),
+                Model::ZeroShotClassificationModel(m) => AspectClassifier::ZeroShot(m),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                    "Expected Sentiment Model or zero shot classification model at resource id {}.",
+                    rid
+                ))
+                }
+            };
+            Ok(predict_aspects(
+                classifier,
+                &init.inputs,
+                &aspects,
+                init.max_length,
+            ))
+        })?;
+
+        Ok(
+            set_result(codec::encode(&outputs).context("Failed to serialize aspect sentiments.")?)
+                as isize,
+        )
+    })
+}
@@ -0,0 +1,199 @@
+use anyhow::Context;
+use rust_bert::bert::{BertConfig, BertConfigResources, BertModelResources, BertVocabResources};
+use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
+use rust_bert::Config;
+use rust_tokenizers::tokenizer::TruncationStrategy;
+use rust_tokenizers::TokenizedInput;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tch::{nn, Device, Tensor};
+
+/// The config of a model that is loaded from the files of a BERT checkpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BertModelInit {
+    /// A directory with a `config.json`, `vocab.txt` and `rust_model.ot`. Defaults to
+    /// `bert-base-uncased` from the Hugging Face hub.
+    pub model_path: Option<String>,
+    /// Whether the tokenizer lowercases the input, defaults to true.
+    pub lower_case: Option<bool>,
+}
+
+/// The local paths of the files of a BERT checkpoint.
+pub struct BertFiles {
+    pub config: PathBuf,
+    pub vocab: PathBuf,
+    pub weights: PathBuf,
+}
+
+impl BertFiles {
+    /// Finds the files in the model directory or downloads the default model.
+    pub fn resolve(model_path: Option<&str>) -> Result<Self, anyhow::Error> {
+        let (config, vocab, weights): (
+            Box<dyn ResourceProvider>,
+            Box<dyn ResourceProvider>,
+            Box<dyn ResourceProvider>,
+        ) = match model_path {
+            Some(dir) => {
+                let dir = Path::new(dir);
+                (
+                    Box::new(LocalResource::from(dir.join("config.json"))),
+                    Box::new(LocalResource::from(dir.join("vocab.txt"))),
+                    Box::new(LocalResource::from(dir.join("rust_model.ot"))),
+                )
+            }
+            None => (
+                Box::new(RemoteResource::from_pretrained(BertConfigResources::BERT)),
+                Box::new(RemoteResource::from_pretrained(BertVocabResources::BERT)),
+                Box::new(RemoteResource::from_pretrained(BertModelResources::BERT)),
+            ),
+        };
+
+        Ok(Self {
+            config: config
+                .get_local_path()
+                .context("Failed to get BERT config.")?,
+            vocab: vocab
+                .get_local_path()
+                .context("Failed to get BERT vocab.")?,
+            weights: weights
+                .get_local_path()
+                .context("Failed to get BERT weights.")?,
+        })
+    }
+
+    pub fn config(&self) -> BertConfig {
+        BertConfig::from_file(&self.config)
+    }
+
+    pub fn tokenizer(&self, lower_case: bool) -> Result<TokenizerOption, anyhow::Error> {
+        TokenizerOption::from_file(
+            ModelType::Bert,
+            self.vocab.to_str().context("Invalid BERT vocab path.")?,
+            None,
+            lower_case,
+            None,
+            None,
+        )
+        .context("Failed to load BERT tokenizer.")
+    }
+
+    /// Creates a var store on the best available device, builds the model in it and loads the
+    /// weights, after evicting models to make room for them.
+    pub fn load<M>(
+        &self,
+        build: impl FnOnce(&nn::Path) -> M,
+    ) -> Result<(nn::VarStore, M), anyhow::Error> {
+        crate::reserve_weights(&self.weights)?;
+        let mut var_store = nn::VarStore::new(Device::cuda_if_available());
+        let model = build(&var_store.root());
+        var_store
+            .load(&self.weights)
+            .context("Failed to load BERT weights.")?;
+        Ok((var_store, model))
+    }
+}
+
+/// The size of the weights of a model.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct WeightStats {
+    pub parameters: u64,
+    pub bytes: u64,
+}
+
+impl WeightStats {
+    pub fn of(var_store: &nn::VarStore) -> Self {
+        var_store.variables().values().fold(
+            Self {
+                parameters: 0,
+                bytes: 0,
+            },
+            |stats, tensor| {
+                let parameters = tensor.numel() as u64;
+                Self {
+                    parameters: stats.parameters + parameters,
+                    bytes: stats.bytes + parameters * tensor.kind().elt_size_in_bytes() as u64,
+                }
+            },
+        )
+    }
+}
+
+/// Tokenizes the inputs and pads them into a batch of token ids and an attention mask on the
+/// device. An empty batch is an error since the tensors can't be stacked.
+pub fn encode_batch(
+    tokenizer: &TokenizerOption,
+    inputs: &[String],
+    max_length: usize,
+    device: Device,
+) -> Result<(Vec<TokenizedInput>, Tensor, Tensor), anyhow::Error> {
+    if inputs.is_empty() {
+        return Err(anyhow::anyhow!("Expected at least one input."));
+    }
+    let pad_id = tokenizer.get_pad_id().unwrap_or(0);
+    let tokenized = tokenizer.encode_list(inputs, max_length, &TruncationStrategy::LongestFirst, 0);
+    let batch_len = tokenized
+        .iter()
+        .map(|t| t.token_ids.len())
+        .max()
+        .unwrap_or(0);
+
+    let (input_ids, attention_mask): (Vec<Tensor>, Vec<Tensor>) = tokenized
+        .iter()
+        .map(|t| {
+            let mut ids = t.token_ids.clone();
+            let mut mask = vec![1i64; ids.len()];
+            ids.resize(batch_len, pad_id);
+            mask.resize(batch_len, 0);
+            (Tensor::of_slice(&ids), Tensor::of_slice(&mask))
+        })
+        .unzip();
+
+    Ok((
+        tokenized,
+        Tensor::stack(&input_ids, 0).to(device),
+        Tensor::stack(&attention_mask, 0).to(device),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenizer() -> TokenizerOption {
+        let vocab = std::env::temp_dir().join(format!("bertml-{}-vocab.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&vocab, "[PAD]\n[UNK]\n[CLS]\n[SEP]\n[MASK]\nhello\nworld\n").unwrap();
+        let tokenizer = BertFiles {
+            config: PathBuf::new(),
+            vocab: vocab.clone(),
+            weights: PathBuf::new(),
+        }
+        .tokenizer(true)
+        .unwrap();
+        std::fs::remove_file(&vocab).unwrap();
+        tokenizer
+    }
+
+    #[test]
+    fn encode_batch_rejects_empty_inputs() {
+        assert!(encode_batch(&tokenizer(), &[], 16, Device::Cpu).is_err());
+    }
+
+    #[test]
+    fn encode_batch_pads_inputs() {
+        let inputs = vec!["hello world".to_string(), "hello".to_string()];
+        let (tokenized, input_ids, attention_mask) =
+            encode_batch(&tokenizer(), &inputs, 16, Device::Cpu).unwrap();
+
+        assert_eq!(tokenized[0].token_ids, vec![2, 5, 6, 3]);
+        assert_eq!(input_ids.size(), vec![2, 4]);
+        let row = |tensor: &Tensor| {
+            (0..4)
+                .map(|j| tensor.int64_value(&[1, j]))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(row(&input_ids), vec![2, 5, 3, 0]);
+        assert_eq!(row(&attention_mask), vec![1, 1, 1, 0]);
+    }
+}
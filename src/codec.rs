@@ -0,0 +1,109 @@
+use crate::ffi;
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The encoding of the structured inputs and results of every FFI function. Raw inputs (e.g.
+/// conversation messages and vectors) and tensor frames are not affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl Codec {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0 => Some(Codec::Json),
+            1 => Some(Codec::MessagePack),
+            2 => Some(Codec::Cbor),
+            _ => None,
+        }
+    }
+}
+
+/// The codec of the library, which is shared by every caller.
+static CODEC: AtomicU8 = AtomicU8::new(0);
+
+pub fn current_codec() -> Codec {
+    Codec::from_u8(CODEC.load(Ordering::Relaxed)).unwrap_or(Codec::Json)
+}
+
+/// Decodes an input with the codec of the library.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, anyhow::Error> {
+    Ok(match current_codec() {
+        Codec::Json => serde_json::from_slice(bytes)?,
+        Codec::MessagePack => rmp_serde::from_slice(bytes)?,
+        Codec::Cbor => ciborium::de::from_reader(bytes)?,
+    })
+}
+
+/// Encodes a result with the codec of the library. Structs are encoded as maps with their
+/// field names in every codec.
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, anyhow::Error> {
+    Ok(match current_codec() {
+        Codec::Json => serde_json::to_vec(value)?,
+        Codec::MessagePack => rmp_serde::to_vec_named(value)?,
+        Codec::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::ser::into_writer(value, &mut bytes)?;
+            bytes
+        }
+    })
+}
+
+/// Sets the codec of the library (0 = JSON, 1 = MessagePack, 2 = CBOR).
+#[ffi]
+#[no_mangle]
+pub extern "C" fn set_codec(codec: u8) -> isize {
+    crate::exec(|| {
+        let codec = Codec::from_u8(codec).context("Unknown codec.")?;
+        CODEC.store(codec as u8, Ordering::Relaxed);
+        Ok(0)
+    })
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn get_codec() -> isize {
+    current_codec() as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Sample {
+        text_value: String,
+        scores: Vec<f32>,
+        id: Option<i64>,
+    }
+
+    #[test]
+    fn round_trips_in_every_codec() {
+        let sample = Sample {
+            text_value: "héllo".to_string(),
+            scores: vec![0.5, -1.25],
+            id: Some(3),
+        };
+        // The codec is global, so every codec is checked in one test
+        for codec in [Codec::Json, Codec::MessagePack, Codec::Cbor] {
+            CODEC.store(codec as u8, Ordering::Relaxed);
+            let bytes = encode(&sample).unwrap();
+            assert_eq!(decode::<Sample>(&bytes).unwrap(), sample);
+        }
+        CODEC.store(Codec::Json as u8, Ordering::Relaxed);
+
+        assert_eq!(
+            String::from_utf8(encode(&sample).unwrap()).unwrap(),
+            r#"{"textValue":"héllo","scores":[0.5,-1.25],"id":3}"#
+        );
+        assert!(decode::<Sample>(b"{").is_err());
+        assert_eq!(Codec::from_u8(3), None);
+    }
+}
@@ -1,22 +1,36 @@
 use crate::{
-    exec, model_resource_accessors, model_resources, models, set_result, Model, ModelResource,
-    ModelResourceAccessor,
+    exec, ffi, model_resource_accessors, model_resources, models, record_vocab, record_weights,
+    set_result, Model, ModelResource, ModelResourceAccessor,
 };
 use anyhow::Context;
-use rust_bert::pipelines::conversation::{ConversationManager, ConversationModel};
+use rust_bert::pipelines::conversation::{
+    ConversationConfig, ConversationManager, ConversationModel,
+};
+use uuid::Uuid;
 
+#[ffi]
 #[no_mangle]
-extern "C" fn create_conversation_model() -> isize {
+pub extern "C" fn create_conversation_model() -> isize {
     exec(|| {
-        let model = ConversationModel::new(Default::default())?;
-        models::allocate(Model::ConversationModel(model))
-            .context("Failed to allocate conversation model.")
-            .map(|a| a as isize)
+        models::load(&[], || {
+            let config = ConversationConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model =
+                ConversationModel::new(config).context("Failed to create conversation model.")?;
+            Ok(Model::ConversationModel(model))
+        })
+        .map(|a| a as isize)
     })
 }
 
+#[ffi]
 #[no_mangle]
-extern "C" fn create_conversation_manager() -> isize {
+pub extern "C" fn create_conversation_manager() -> isize {
     exec(|| {
         let manager = ConversationManager::new();
         model_resources::allocate(ModelResource::ConversationManager(manager))
@@ -25,8 +39,9 @@ extern "C" fn create_conversation_manager() -> isize {
     })
 }
 
+#[ffi]
 #[no_mangle]
-extern "C" fn create_conversation(rid: usize) -> isize {
+pub extern "C" fn create_conversation(rid: usize) -> isize {
     exec(|| {
         let convo_id = model_resources::with_access(rid, |resource| {
             let conversation_manager = match resource {
@@ -46,8 +61,28 @@ extern "C" fn create_conversation(rid: usize) -> isize {
     })
 }
 
+/// Adds the text to the conversation and returns the response of the model to it.
+pub fn send_message(
+    model: &ConversationModel,
+    manager: &mut ConversationManager,
+    convo_id: &Uuid,
+    text: &str,
+) -> Result<String, anyhow::Error> {
+    manager
+        .get(convo_id)
+        .context("Failed to conversation.")?
+        .add_user_input(text)?;
+
+    let responses = model.generate_responses(manager);
+    Ok(responses
+        .get(convo_id)
+        .context("Failed to get conversation model response.")?
+        .to_string())
+}
+
+#[ffi]
 #[no_mangle]
-extern "C" fn conversation_send(
+pub extern "C" fn conversation_send(
     model_rid: usize,
     manager_rid: usize,
     convo_rid: usize,
@@ -55,8 +90,8 @@ extern "C" fn conversation_send(
     text_len: usize,
 ) -> isize {
     exec(|| {
-        let text =
-            unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(text, text_len)) };
+        let text = std::str::from_utf8(unsafe { std::slice::from_raw_parts(text, text_len) })
+            .context("Expected conversation text to be UTF-8.")?;
 
         model_resource_accessors::with_access(convo_rid, |res| {
             let convo_id = match res {
@@ -70,7 +105,7 @@ extern "C" fn conversation_send(
             };
 
             model_resources::with_access(manager_rid, |model| {
-                let mut conversation_manager = match model {
+                let conversation_manager = match model {
                     ModelResource::ConversationManager(d) => d,
                     _ => {
                         return Err(anyhow::anyhow!(
@@ -80,14 +115,6 @@ extern "C" fn conversation_send(
                     }
                 };
 
-                let convo = conversation_manager
-                    .get(convo_id)
-                    .context("Failed to conversation.")?;
-
-                convo.add_user_input(text)?;
-
-                drop(convo);
-
                 models::with_access(model_rid, |model| {
                     let model = match model {
                         Model::ConversationModel(m) => m,
@@ -99,17 +126,8 @@ extern "C" fn conversation_send(
                         }
                     };
 
-                    let responses = model.generate_responses(&mut conversation_manager);
-
-                    Ok(set_result(
-                        String::from(
-                            responses
-                                .get(convo_id)
-                                .context("Failed to get conversation model response.")?
-                                .clone(),
-                        )
-                        .into_bytes(),
-                    ) as isize)
+                    let response = send_message(model, conversation_manager, convo_id, text)?;
+                    Ok(set_result(response.into_bytes()) as isize)
                 })
             })
         })
@@ -0,0 +1,183 @@
+use crate::{
+    codec, encode_batch, exec, ffi, models, set_result, BertFiles, BertModelInit, Model,
+    TensorFrame, WeightStats,
+};
+use anyhow::Context;
+use rust_bert::bert::{BertEmbeddings, BertModel};
+use rust_bert::pipelines::common::TokenizerOption;
+use serde::{Deserialize, Serialize};
+use tch::{nn, no_grad, Tensor};
+
+const MAX_LENGTH: usize = 512;
+
+/// A BERT encoder without a task head, for reading its hidden states and attentions.
+pub struct EncoderModel {
+    tokenizer: TokenizerOption,
+    model: BertModel<BertEmbeddings>,
+    var_store: nn::VarStore,
+    max_length: usize,
+}
+
+impl EncoderModel {
+    pub fn new(init: &BertModelInit) -> Result<Self, anyhow::Error> {
+        let files = BertFiles::resolve(init.model_path.as_deref())?;
+        let mut config = files.config();
+        // Every layer is kept so that each call can choose what it returns.
+        config.output_hidden_states = Some(true);
+        config.output_attentions = Some(true);
+        let tokenizer = files.tokenizer(init.lower_case.unwrap_or(true))?;
+        // BERT checkpoints keep the weights of the encoder under the `bert` prefix, next to the
+        // weights of their task heads, like `BertForMaskedLM` builds it.
+        let (var_store, model) =
+            files.load(|p| BertModel::<BertEmbeddings>::new(&(p / "bert"), &config))?;
+
+        Ok(Self {
+            tokenizer,
+            model,
+            var_store,
+            max_length: config.max_position_embeddings as usize,
+        })
+    }
+
+    pub fn tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length.min(MAX_LENGTH)
+    }
+
+    pub fn weights(&self) -> WeightStats {
+        WeightStats::of(&self.var_store)
+    }
+
+    /// Runs the encoder, keeping the layers that `init` asks for.
+    pub fn forward(&self, init: &EncoderInit) -> Result<EncoderOutput, anyhow::Error> {
+        let (tokenized, input_ids, attention_mask) = encode_batch(
+            &self.tokenizer,
+            &init.inputs,
+            self.max_length(),
+            self.var_store.device(),
+        )?;
+
+        let output = no_grad(|| {
+            self.model.forward_t(
+                Some(&input_ids),
+                Some(&attention_mask),
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+            )
+        })
+        .context("Failed to run encoder.")?;
+
+        Ok(EncoderOutput {
+            last_hidden_state: output.hidden_state,
+            pooled_output: output.pooled_output,
+            hidden_states: output
+                .all_hidden_states
+                .filter(|_| init.all_layers)
+                .map(|layers| Tensor::stack(&layers, 0)),
+            attentions: output
+                .all_attentions
+                .filter(|_| init.attentions)
+                .map(|layers| Tensor::stack(&layers, 0)),
+            token_ids: tokenized.into_iter().map(|t| t.token_ids).collect(),
+        })
+    }
+
+    /// Runs the encoder and collects the requested outputs into a tensor frame.
+    pub fn encode(&self, init: &EncoderInit) -> Result<Vec<u8>, anyhow::Error> {
+        let output = self.forward(init)?;
+
+        let mut frame = TensorFrame::new();
+        frame.push("lastHiddenState", &output.last_hidden_state);
+        if let Some(pooled) = &output.pooled_output {
+            frame.push("pooledOutput", pooled);
+        }
+        if let Some(layers) = &output.hidden_states {
+            frame.push("hiddenStates", layers);
+        }
+        if let Some(attentions) = &output.attentions {
+            frame.push("attentions", attentions);
+        }
+
+        frame.into_bytes(EncoderMetadata {
+            token_ids: output.token_ids,
+        })
+    }
+}
+
+/// The outputs of the encoder for a batch of inputs.
+pub struct EncoderOutput {
+    /// `[batch, tokens, hidden]`
+    pub last_hidden_state: Tensor,
+    /// `[batch, hidden]`
+    pub pooled_output: Option<Tensor>,
+    /// `[layers, batch, tokens, hidden]`
+    pub hidden_states: Option<Tensor>,
+    /// `[layers, batch, heads, tokens, tokens]`
+    pub attentions: Option<Tensor>,
+    /// The token ids of each input without padding.
+    pub token_ids: Vec<Vec<i64>>,
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_encoder_model(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: BertModelInit =
+            codec::decode(bytes).context("Failed to parse encoder model config.")?;
+
+        models::load(bytes, move || {
+            let model = EncoderModel::new(&init).context("Failed to create encoder model.")?;
+            Ok(Model::EncoderModel(model))
+        })
+        .map(|rid| rid as isize)
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderInit {
+    pub inputs: Vec<String>,
+    /// Whether to return the hidden states of every layer as `hiddenStates`, with the shape
+    /// `[layers, batch, tokens, hidden]`.
+    #[serde(default)]
+    pub all_layers: bool,
+    /// Whether to return the attention weights of every layer as `attentions`, with the shape
+    /// `[layers, batch, heads, tokens, tokens]`.
+    #[serde(default)]
+    pub attentions: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EncoderMetadata {
+    /// The token ids of each input without padding. The rows after them are padding.
+    token_ids: Vec<Vec<i64>>,
+}
+
+/// Sets the result to a tensor frame (see `TensorFrame`) with `lastHiddenState`
+/// (`[batch, tokens, hidden]`), `pooledOutput` (`[batch, hidden]`) and the optional layers.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn encoder_forward(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: EncoderInit = codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            .context("Failed to parse encoder input.")?;
+
+        models::with_access(rid, |model| {
+            let model = match model {
+                Model::EncoderModel(m) => m,
+                _ => return Err(anyhow::anyhow!("Expected encoder model at rid '{}'.", rid)),
+            };
+
+            Ok(set_result(model.encode(&init)?) as isize)
+        })
+    })
+}
@@ -0,0 +1,305 @@
+use crate::{
+    codec, encode_batch, exec, ffi, models, set_result, BertFiles, BertModelInit, Model,
+    ResultFormat, TensorFrame, WeightStats,
+};
+use anyhow::Context;
+use rust_bert::bert::BertForMaskedLM;
+use rust_bert::pipelines::common::TokenizerOption;
+use serde::{Deserialize, Serialize};
+use tch::{nn, no_grad, Kind, Tensor};
+
+const MASK_TOKEN: &str = "[MASK]";
+const MAX_LENGTH: usize = 512;
+
+/// A BERT masked language model that predicts the most probable tokens for masks.
+pub struct FillMaskModel {
+    tokenizer: TokenizerOption,
+    model: BertForMaskedLM,
+    var_store: nn::VarStore,
+    max_length: usize,
+}
+
+impl FillMaskModel {
+    pub fn new(init: &BertModelInit) -> Result<Self, anyhow::Error> {
+        let files = BertFiles::resolve(init.model_path.as_deref())?;
+        let config = files.config();
+        let tokenizer = files.tokenizer(init.lower_case.unwrap_or(true))?;
+        let (var_store, model) = files.load(|p| BertForMaskedLM::new(p, &config))?;
+
+        Ok(Self {
+            tokenizer,
+            model,
+            var_store,
+            max_length: config.max_position_embeddings as usize,
+        })
+    }
+
+    pub fn tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length.min(MAX_LENGTH)
+    }
+
+    pub fn weights(&self) -> WeightStats {
+        WeightStats::of(&self.var_store)
+    }
+
+    /// Gets the `top_k` candidates for each mask of each input, where masks are written as
+    /// `mask_token`. There are fewer candidates when the vocab is smaller than `top_k`. Inputs
+    /// with masks past the max length are an error.
+    pub fn predict(
+        &self,
+        inputs: &[String],
+        mask_token: &str,
+        top_k: usize,
+    ) -> Result<Vec<Vec<MaskPrediction>>, anyhow::Error> {
+        if mask_token.is_empty() {
+            return Err(anyhow::anyhow!("Expected a mask token that is not empty."));
+        }
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mask_id = self
+            .tokenizer
+            .get_mask_id()
+            .context("The tokenizer has no mask token.")?;
+
+        let inputs = inputs
+            .iter()
+            .map(|input| input.replace(mask_token, MASK_TOKEN))
+            .collect::<Vec<_>>();
+        let (tokenized, input_ids, attention_mask) = encode_batch(
+            &self.tokenizer,
+            &inputs,
+            self.max_length(),
+            self.var_store.device(),
+        )?;
+
+        // The input and position of each mask
+        let masks = tokenized
+            .iter()
+            .enumerate()
+            .flat_map(|(i, t)| {
+                t.token_ids
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &id)| id == mask_id)
+                    .map(move |(position, _)| (i, position))
+            })
+            .collect::<Vec<_>>();
+        // A mask that was truncated away would silently have no prediction
+        for (i, input) in inputs.iter().enumerate() {
+            let expected = input.matches(MASK_TOKEN).count();
+            let found = masks.iter().filter(|(j, _)| *j == i).count();
+            if found < expected {
+                return Err(anyhow::anyhow!(
+                    "Input {} has {} masks but only {} are within the first {} tokens.",
+                    i,
+                    expected,
+                    found,
+                    self.max_length()
+                ));
+            }
+        }
+        let mut predictions: Vec<Vec<MaskPrediction>> =
+            tokenized.iter().map(|_| Vec::new()).collect();
+        if masks.is_empty() {
+            return Ok(predictions);
+        }
+
+        let (scores, ids) = no_grad(|| {
+            let prediction_scores = self
+                .model
+                .forward_t(
+                    Some(&input_ids),
+                    Some(&attention_mask),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .prediction_scores;
+            let mask_scores = Tensor::stack(
+                &masks
+                    .iter()
+                    .map(|&(i, position)| prediction_scores.get(i as i64).get(position as i64))
+                    .collect::<Vec<_>>(),
+                0,
+            );
+            let vocab_size = mask_scores.size()[1];
+            mask_scores.softmax(-1, Kind::Float).topk(
+                (top_k as i64).min(vocab_size),
+                -1,
+                true,
+                true,
+            )
+        });
+
+        let k = ids.size()[1];
+        for (row, &(i, _)) in masks.iter().enumerate() {
+            let row = row as i64;
+            predictions[i].push(MaskPrediction {
+                ids: (0..k).map(|j| ids.int64_value(&[row, j])).collect(),
+                scores: (0..k)
+                    .map(|j| scores.double_value(&[row, j]) as f32)
+                    .collect(),
+            });
+        }
+        Ok(predictions)
+    }
+
+    /// Same as `predict`, with the text of each candidate token.
+    pub fn candidates(
+        &self,
+        inputs: &[String],
+        mask_token: &str,
+        top_k: usize,
+    ) -> Result<Vec<Vec<Vec<JSMaskCandidate>>>, anyhow::Error> {
+        Ok(self
+            .predict(inputs, mask_token, top_k)?
+            .into_iter()
+            .map(|masks| {
+                masks
+                    .into_iter()
+                    .map(|m| {
+                        m.ids
+                            .into_iter()
+                            .zip(m.scores)
+                            .map(|(id, score)| JSMaskCandidate {
+                                id,
+                                text: self.tokenizer.decode(&[id], false, true),
+                                score,
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// The most probable tokens for a mask, from the most to the least probable.
+pub struct MaskPrediction {
+    pub ids: Vec<i64>,
+    pub scores: Vec<f32>,
+}
+
+/// A token that could fill a mask.
+#[derive(Debug, Serialize)]
+pub struct JSMaskCandidate {
+    pub id: i64,
+    pub text: String,
+    /// The probability of the token at the mask.
+    pub score: f32,
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_fill_mask_model(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: BertModelInit =
+            codec::decode(bytes).context("Failed to parse fill-mask model config.")?;
+
+        models::load(bytes, move || {
+            let model = FillMaskModel::new(&init).context("Failed to create fill-mask model.")?;
+            Ok(Model::FillMaskModel(model))
+        })
+        .map(|rid| rid as isize)
+    })
+}
+
+fn default_top_k() -> usize {
+    5
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillMaskInit {
+    pub inputs: Vec<String>,
+    /// The string that marks the masks in the inputs, defaults to `[MASK]`.
+    pub mask_token: Option<String>,
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    /// With `tensor`, the result has `ids` (`i64`) and `scores` tensors of the shape
+    /// `[masks, topK]` for the masks of all inputs and a `maskCounts` tensor with the number of
+    /// masks of each input.
+    #[serde(default)]
+    pub format: ResultFormat,
+}
+
+impl FillMaskInit {
+    /// The string that marks the masks in the inputs.
+    pub fn mask_token(&self) -> &str {
+        self.mask_token.as_deref().unwrap_or(MASK_TOKEN)
+    }
+}
+
+fn to_tensor_frame(predictions: &[Vec<MaskPrediction>], top_k: usize) -> TensorFrame {
+    let masks = predictions.iter().flatten();
+    // Fewer than `top_k` tokens when the vocab is smaller
+    let k = masks.clone().next().map_or(top_k, |m| m.ids.len());
+    let shape = vec![masks.clone().count() as i64, k as i64];
+
+    let mut frame = TensorFrame::new();
+    frame.push_i64(
+        "ids",
+        shape.clone(),
+        &masks
+            .clone()
+            .flat_map(|m| m.ids.clone())
+            .collect::<Vec<_>>(),
+    );
+    frame.push_f32(
+        "scores",
+        shape,
+        &masks.flat_map(|m| m.scores.clone()).collect::<Vec<_>>(),
+    );
+    frame.push_i64(
+        "maskCounts",
+        vec![predictions.len() as i64],
+        &predictions
+            .iter()
+            .map(|p| p.len() as i64)
+            .collect::<Vec<_>>(),
+    );
+    frame
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn fill_mask_predict(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: FillMaskInit = codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            .context("Failed to parse fill-mask input.")?;
+
+        models::with_access(rid, |model| {
+            let model = match model {
+                Model::FillMaskModel(m) => m,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Expected fill-mask model at rid '{}'.",
+                        rid
+                    ))
+                }
+            };
+
+            let mask_token = init.mask_token();
+            let outputs = match init.format {
+                ResultFormat::Tensor => {
+                    let predictions = model.predict(&init.inputs, mask_token, init.top_k)?;
+                    to_tensor_frame(&predictions, init.top_k).into_bytes(())?
+                }
+                ResultFormat::Json => {
+                    codec::encode(&model.candidates(&init.inputs, mask_token, init.top_k)?)
+                        .context("Failed to serialize fill-mask output.")?
+                }
+            };
+            Ok(set_result(outputs) as isize)
+        })
+    })
+}
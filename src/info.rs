@@ -0,0 +1,116 @@
+use crate::{exec, ffi, model_resources, models, set_result, Model, ModelResource};
+use serde::Serialize;
+use tch::Cuda;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub bertml: &'static str,
+    pub abi: u32,
+    /// The versions that cargo resolved, unset when `cargo metadata` failed during the build.
+    pub rust_bert: Option<&'static str>,
+    pub tch: Option<&'static str>,
+    /// The version of the libtorch at `LIBTORCH` during the build, unset when it was found
+    /// another way.
+    pub libtorch: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuInfo {
+    /// The number of threads that libtorch uses within an operation.
+    pub threads: i32,
+    /// The number of threads that libtorch uses to run operations in parallel.
+    pub interop_threads: i32,
+    pub mkl: bool,
+    pub openmp: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CudaInfo {
+    pub available: bool,
+    pub devices: i64,
+    pub cudnn: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceInfo {
+    pub cpu: CpuInfo,
+    pub cuda: CudaInfo,
+}
+
+/// An allocated model or resource.
+#[derive(Debug, Serialize)]
+pub struct AllocationInfo {
+    pub rid: usize,
+    pub kind: &'static str,
+}
+
+/// Describes the build of the library, the devices it can run on and what it has allocated.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryInfo {
+    pub versions: VersionInfo,
+    /// The cargo features that the library was built with.
+    pub features: Vec<&'static str>,
+    /// The kinds of pipeline models that can be created.
+    pub pipelines: Vec<&'static str>,
+    /// The kinds of resources (e.g. vector indices) that can be created.
+    pub resources: &'static [&'static str],
+    pub devices: DeviceInfo,
+    pub models: Vec<AllocationInfo>,
+    pub model_resources: Vec<AllocationInfo>,
+}
+
+pub fn info() -> LibraryInfo {
+    LibraryInfo {
+        versions: VersionInfo {
+            bertml: env!("CARGO_PKG_VERSION"),
+            abi: crate::BERTML_ABI_VERSION,
+            rust_bert: option_env!("RUST_BERT_VERSION"),
+            tch: option_env!("TCH_VERSION"),
+            libtorch: option_env!("LIBTORCH_VERSION"),
+        },
+        features: env!("BERTML_FEATURES")
+            .split(',')
+            .filter(|feature| !feature.is_empty())
+            .collect(),
+        // Tokenizers are models too but not pipelines
+        pipelines: Model::KINDS
+            .iter()
+            .copied()
+            .filter(|&kind| kind != "Tokenizer")
+            .collect(),
+        resources: ModelResource::KINDS,
+        devices: DeviceInfo {
+            cpu: CpuInfo {
+                threads: tch::get_num_threads(),
+                interop_threads: tch::get_num_interop_threads(),
+                mkl: tch::utils::has_mkl(),
+                openmp: tch::utils::has_openmp(),
+            },
+            cuda: CudaInfo {
+                available: Cuda::is_available(),
+                devices: Cuda::device_count(),
+                cudnn: Cuda::cudnn_is_available(),
+            },
+        },
+        models: models::map_all(|rid, allocation| AllocationInfo {
+            rid,
+            kind: allocation.kind,
+        }),
+        model_resources: model_resources::map_all(|rid, allocation| AllocationInfo {
+            rid,
+            kind: allocation.kind,
+        }),
+    }
+}
+
+/// Sets the result to the `LibraryInfo` of the library, which is always JSON like the symbol
+/// manifest.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn bertml_info() -> isize {
+    exec(|| Ok(set_result(serde_json::to_vec(&info())?) as isize))
+}
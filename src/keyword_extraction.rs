@@ -0,0 +1,202 @@
+use crate::{
+    codec, exec, ffi, models, record_vocab, record_weights, sentence_embeddings_config, set_result,
+    text, Model,
+};
+use anyhow::Context;
+use once_cell::sync::Lazy;
+use rust_bert::pipelines::keywords_extraction::{
+    Keyword, KeywordExtractionConfig, KeywordExtractionModel, KeywordScorerType,
+};
+use rust_bert::pipelines::sentence_embeddings::SentenceEmbeddingsModelType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Every custom stopword of the keyword extraction models. The models borrow their stopwords for
+/// the lifetime of the library, so each distinct stopword is leaked once and shared by every model
+/// (and every reload of a model) that uses it.
+static STOPWORDS: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Gets the lowercased stopwords from the interned stopwords, adding the ones that are new.
+fn intern_stopwords(stopwords: &[String]) -> HashSet<&'static str> {
+    let mut interned = STOPWORDS.lock().unwrap();
+    stopwords
+        .iter()
+        .map(|stopword| {
+            let stopword = stopword.to_lowercase();
+            match interned.get(stopword.as_str()) {
+                Some(&stopword) => stopword,
+                None => {
+                    let stopword: &'static str = Box::leak(stopword.into_boxed_str());
+                    interned.insert(stopword);
+                    stopword
+                }
+            }
+        })
+        .collect()
+}
+
+/// How the keyphrases are ranked.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeywordScorer {
+    /// By the similarity of the keyphrase to the document.
+    Cosine,
+    /// By Maximal Marginal Relevance, which trades similarity to the document for diversity.
+    Mmr,
+    /// By the least similar combination of the candidates that are the most similar to the
+    /// document.
+    MaxSum,
+}
+
+impl From<KeywordScorer> for KeywordScorerType {
+    fn from(scorer: KeywordScorer) -> Self {
+        match scorer {
+            KeywordScorer::Cosine => KeywordScorerType::CosineSimilarity,
+            KeywordScorer::Mmr => KeywordScorerType::MaximalMarginRelevance,
+            KeywordScorer::MaxSum => KeywordScorerType::MaxSum,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordExtractionModelInit {
+    /// The directory of a local SBERT-style model. Defaults to `all-MiniLM-L6-v2` from the
+    /// Hugging Face hub.
+    pub model_path: Option<String>,
+    /// The smallest and largest number of words in a keyphrase, defaults to `[1, 1]`.
+    pub ngram_range: Option<(usize, usize)>,
+    /// Replaces the default English stopwords. Keyphrases do not start or end with stopwords.
+    pub stopwords: Option<Vec<String>>,
+    /// The maximum number of keyphrases for each input, defaults to 5.
+    pub max_keywords: Option<usize>,
+    pub scorer: Option<KeywordScorer>,
+    /// The diversity of MMR between 0 and 1, defaults to 0.5.
+    pub diversity: Option<f64>,
+    /// The number of candidates that max-sum combines, defaults to 20.
+    pub max_sum_candidates: Option<usize>,
+}
+
+impl KeywordExtractionModelInit {
+    pub fn create(&self) -> Result<KeywordExtractionModel<'static>, anyhow::Error> {
+        let defaults = KeywordExtractionConfig::default();
+        let config = KeywordExtractionConfig {
+            sentence_embeddings_config: sentence_embeddings_config(
+                self.model_path.clone(),
+                SentenceEmbeddingsModelType::AllMiniLmL6V2,
+                None,
+            )?,
+            tokenizer_stopwords: match &self.stopwords {
+                Some(stopwords) => Some(intern_stopwords(stopwords)),
+                None => defaults.tokenizer_stopwords,
+            },
+            ngram_range: self.ngram_range.unwrap_or(defaults.ngram_range),
+            num_keywords: self.max_keywords.unwrap_or(defaults.num_keywords),
+            scorer_type: self.scorer.map(Into::into).unwrap_or(defaults.scorer_type),
+            diversity: self.diversity.or(defaults.diversity),
+            max_sum_candidates: self.max_sum_candidates.or(defaults.max_sum_candidates),
+            ..defaults
+        };
+
+        let embeddings = &config.sentence_embeddings_config;
+        record_weights(&*embeddings.transformer_weights_resource)?;
+        record_vocab(
+            embeddings.transformer_type,
+            &*embeddings.tokenizer_vocab_resource,
+            embeddings.tokenizer_merges_resource.as_deref(),
+        )?;
+        KeywordExtractionModel::new(config).context("Failed to create keyword extraction model.")
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_keyword_extraction_model(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: KeywordExtractionModelInit =
+            codec::decode(bytes).context("Failed to parse keyword extraction model config.")?;
+
+        models::load(bytes, move || {
+            Ok(Model::KeywordExtractionModel(init.create()?))
+        })
+        .map(|rid| rid as isize)
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct JSKeyword {
+    pub text: String,
+    pub score: f32,
+    /// Every occurrence of the keyphrase in the input.
+    pub occurrences: Vec<text::TextSpan>,
+}
+
+impl JSKeyword {
+    fn from_keyword(keyword: Keyword, input: &str) -> Self {
+        Self {
+            occurrences: keyword
+                .offsets
+                .iter()
+                .map(|o| (o.begin as usize, o.end as usize))
+                // The offsets are bytes into the input, skip any that do not line up with it
+                .filter(|&(start, end)| {
+                    start <= end && input.is_char_boundary(start) && input.is_char_boundary(end)
+                })
+                .map(|(start, end)| text::TextSpan::from_byte_range(input, start, end))
+                .collect(),
+            text: keyword.text,
+            score: keyword.score,
+        }
+    }
+}
+
+/// Extracts the keyphrases of each input.
+pub fn extract_keywords(
+    model: &KeywordExtractionModel,
+    inputs: &[String],
+) -> Result<Vec<Vec<JSKeyword>>, anyhow::Error> {
+    // rust-bert can't predict an empty batch
+    if inputs.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(model
+        .predict(inputs)
+        .context("Failed to extract keywords.")?
+        .into_iter()
+        .zip(inputs)
+        .map(|(keywords, input)| {
+            keywords
+                .into_iter()
+                .map(|k| JSKeyword::from_keyword(k, input))
+                .collect()
+        })
+        .collect())
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn keywords_extract(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let inputs: Vec<String> =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse keyword extraction inputs.")?;
+
+        models::with_access(rid, |model| {
+            let model = match model {
+                Model::KeywordExtractionModel(m) => m,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Expected keyword extraction model at rid '{}'.",
+                        rid
+                    ))
+                }
+            };
+
+            let outputs = codec::encode(&extract_keywords(model, &inputs)?)
+                .context("Failed to serialize keywords.")?;
+            Ok(set_result(outputs) as isize)
+        })
+    })
+}
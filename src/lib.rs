@@ -1,25 +1,66 @@
+//! Bindings to the pipelines of `rust-bert`.
+//!
+//! Every module has a typed Rust API, made of the request and response structs and functions that
+//! take the models directly, and a thin layer of `extern "C"` functions over it that decode the
+//! requests, keep the models in the allocators and encode the results for the FFI. The crate is
+//! built both as an `rlib` for Rust dependents and as a `cdylib` for the FFI.
+//!
+//! The exported functions are marked with `#[ffi]`, which adds them to the symbol manifest
+//! (`symbols`) that the symbol table of the Deno bindings is generated from.
+
 mod allocators;
+mod aspect_sentiment;
+mod bert;
+mod codec;
 mod conversation;
+mod encoder;
+mod fill_mask;
+mod info;
+mod keyword_extraction;
 mod ner;
 mod pos_tagging;
 mod qa;
+mod qa_corpus;
+mod sentence_embeddings;
 mod sentiment;
+mod stats;
 mod summarization;
+mod symbols;
+mod tensor;
+mod text;
 mod text_generation;
+mod tokenizer;
 mod translation;
+mod vector_index;
 mod zero_shot_classification;
 
 pub use allocators::*;
+pub use aspect_sentiment::*;
+pub use bert::*;
+use bertml_macros::ffi;
+pub use codec::*;
 pub use conversation::*;
+pub use encoder::*;
+pub use fill_mask::*;
+pub use info::*;
+pub use keyword_extraction::*;
 pub use ner::*;
 use once_cell::sync::Lazy;
 pub use pos_tagging::*;
 pub use qa::*;
+pub use qa_corpus::*;
+pub use sentence_embeddings::*;
 pub use sentiment::*;
+pub use stats::*;
 use std::sync::Mutex;
 pub use summarization::*;
+pub use symbols::*;
+pub use tensor::*;
+pub use text::*;
 pub use text_generation::*;
+pub use tokenizer::*;
 pub use translation::*;
+pub use vector_index::*;
 pub use zero_shot_classification::*;
 
 pub static LAST_ERROR: Lazy<Mutex<Vec<u8>>> = Lazy::new(|| Mutex::new(Vec::new()));
@@ -47,29 +88,64 @@ where
     }
 }
 
+/// The version of the C ABI in `include/bertml.h`. It is bumped whenever an exported function is
+/// removed or its signature, encoding or the meaning of its arguments changes.
+pub const BERTML_ABI_VERSION: u32 = 3;
+
+/// Returns the version of the C ABI of the library, to be compared with `BERTML_ABI_VERSION` of
+/// the header the caller was built with.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn bertml_abi_version() -> u32 {
+    BERTML_ABI_VERSION
+}
+
+/// Moves the result of the last call into the buffer, which must be as long as the length that
+/// the call returned.
+#[ffi]
 #[no_mangle]
-extern "C" fn fill_result(buf: *mut u8, buf_len: usize) {
+pub extern "C" fn fill_result(buf: *mut u8, buf_len: usize) {
     let buf = unsafe { std::slice::from_raw_parts_mut(buf, buf_len) };
     buf.swap_with_slice(&mut *LAST_RESULT.lock().unwrap())
 }
 
+/// Returns the length of the message of the last error, for a call that returned -1.
+#[ffi]
 #[no_mangle]
-extern "C" fn error_len() -> usize {
+pub extern "C" fn error_len() -> usize {
     LAST_ERROR.lock().unwrap().len()
 }
 
+/// Moves the message of the last error (UTF-8) into the buffer, which must be `error_len()` long.
+#[ffi]
 #[no_mangle]
-extern "C" fn fill_error(buf: *mut u8, buf_len: usize) {
+pub extern "C" fn fill_error(buf: *mut u8, buf_len: usize) {
     let buf = unsafe { std::slice::from_raw_parts_mut(buf, buf_len) };
     buf.swap_with_slice(&mut *LAST_ERROR.lock().unwrap());
 }
 
+/// Deletes the model at the rid.
+#[ffi]
 #[no_mangle]
-extern "C" fn delete_model(rid: usize) -> i32 {
-    match models::deallocate(rid) {
-        Ok(_) => 0,
-        Err(_) => -1,
-    }
+pub extern "C" fn delete_model(rid: usize) -> isize {
+    exec(|| models::deallocate(rid).map(|_| 0))
+}
+
+/// Deletes the model resource (e.g. a vector index or conversation manager) at the rid.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn delete_model_resource(rid: usize) -> isize {
+    exec(|| model_resources::deallocate(rid).map(|_| 0))
+}
+
+/// Sets the bytes of memory that the loaded models may take up, or removes the budget with 0.
+/// When a model doesn't fit, the least recently used models are unloaded and then loaded again
+/// from their config on their next use.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn set_model_memory_budget(bytes: usize) -> isize {
+    models::set_memory_budget(Some(bytes as u64).filter(|&bytes| bytes > 0));
+    0
 }
 
 #[cfg(test)]
@@ -1,22 +1,33 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, Model};
 use anyhow::Context;
 use rust_bert::pipelines::ner::{self, NERModel};
+use rust_bert::pipelines::token_classification::TokenClassificationConfig;
 use serde::{Deserialize, Serialize};
 
+#[ffi]
 #[no_mangle]
-extern "C" fn create_ner_model() -> isize {
+pub extern "C" fn create_ner_model() -> isize {
     exec(|| {
-        let model = NERModel::new(Default::default()).context("Failed to create NER model.")?;
-
-        models::allocate(Model::NERModel(model)).map(|rid| rid as isize)
+        models::load(&[], || {
+            let config = TokenClassificationConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model = NERModel::new(config).context("Failed to create NER model.")?;
+            Ok(Model::NERModel(model))
+        })
+        .map(|rid| rid as isize)
     })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NEREntity {
-    word: String,
-    score: f64,
-    label: String,
+    pub word: String,
+    pub score: f64,
+    pub label: String,
 }
 
 impl From<ner::Entity> for NEREntity {
@@ -29,12 +40,22 @@ impl From<ner::Entity> for NEREntity {
     }
 }
 
+/// Finds the entities in each of the inputs.
+pub fn predict_entities(model: &NERModel, inputs: &[String]) -> Vec<Vec<NEREntity>> {
+    model
+        .predict(inputs)
+        .into_iter()
+        .map(|v| v.into_iter().map(|e| e.into()).collect())
+        .collect()
+}
+
+#[ffi]
 #[no_mangle]
-extern "C" fn ner_predict(rid: usize, input: *const u8, input_len: usize) -> isize {
+pub extern "C" fn ner_predict(rid: usize, input: *const u8, input_len: usize) -> isize {
     exec(|| {
         let input = unsafe { std::slice::from_raw_parts(input, input_len) };
         let input: Vec<String> =
-            serde_json::from_slice(input).context("Failed to parse NER model input.")?;
+            codec::decode(input).context("Failed to parse NER model input.")?;
 
         let entities = models::with_access(rid, move |model| {
             let model = match model {
@@ -47,15 +68,10 @@ extern "C" fn ner_predict(rid: usize, input: *const u8, input_len: usize) -> isi
                 }
             };
 
-            Ok(model.predict(&input))
+            Ok(predict_entities(model, &input))
         })?;
 
-        let entities: Vec<Vec<NEREntity>> = entities
-            .into_iter()
-            .map(|v| v.into_iter().map(|e| e.into()).collect())
-            .collect();
-
-        let entities = serde_json::to_vec(&entities)
+        let entities = codec::encode(&entities)
             .context("Failed to serialize entities response from NER model.")?;
 
         Ok(set_result(entities) as isize)
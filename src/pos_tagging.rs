@@ -1,22 +1,128 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, text, Model};
 use anyhow::Context;
-use rust_bert::pipelines::pos_tagging::POSModel;
+use rust_bert::pipelines::pos_tagging::{POSConfig, POSModel, POSTag};
+use serde::{Deserialize, Serialize};
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn create_pos_model() -> isize {
     exec(|| {
-        let model = POSModel::new(Default::default())
-            .context("Failed to load Parts of Speech Tagging model.")?;
-
-        models::allocate(Model::POSModel(model)).map(|a| a as isize)
+        models::load(&[], || {
+            let config = POSConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model =
+                POSModel::new(config).context("Failed to load Parts of Speech Tagging model.")?;
+            Ok(Model::POSModel(model))
+        })
+        .map(|a| a as isize)
     })
 }
 
+/// The tag set of the labels in the output.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagSet {
+    /// The Penn Treebank tags that the model predicts (e.g. `NNS`, `VBZ`).
+    #[default]
+    Ptb,
+    /// The Universal Dependencies part of speech tags (e.g. `NOUN`, `VERB`).
+    Ud,
+}
+
+/// Maps a Penn Treebank tag to its Universal Dependencies part of speech tag.
+pub fn ptb_to_ud(tag: &str) -> &'static str {
+    match tag {
+        "NN" | "NNS" => "NOUN",
+        "NNP" | "NNPS" => "PROPN",
+        "VB" | "VBD" | "VBG" | "VBN" | "VBP" | "VBZ" => "VERB",
+        "MD" => "AUX",
+        "JJ" | "JJR" | "JJS" | "AFX" => "ADJ",
+        "RB" | "RBR" | "RBS" | "WRB" => "ADV",
+        "PRP" | "PRP$" | "WP" | "WP$" | "EX" => "PRON",
+        "DT" | "PDT" | "WDT" => "DET",
+        "IN" => "ADP",
+        "CC" => "CCONJ",
+        "CD" => "NUM",
+        "RP" | "TO" | "POS" => "PART",
+        "UH" => "INTJ",
+        "SYM" | "$" | "#" => "SYM",
+        "." | "," | ":" | "``" | "''" | "\"" | "-LRB-" | "-RRB-" | "(" | ")" | "HYPH" | "NFP" => {
+            "PUNCT"
+        }
+        _ => "X",
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct POSPredictInit {
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub tag_set: TagSet,
+}
+
+/// A tagged word, see `POSEntity` in `models/pos.ts` for the documented schema.
+#[derive(Debug, Serialize)]
+pub struct JSPOSTag {
+    pub word: String,
+    pub score: f64,
+    pub label: String,
+    /// The UTF-16 offsets of the word in the input, `None` if the word could not be found (e.g.
+    /// when the tokenizer strips accents).
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+/// Converts the tags of an input and finds the offsets of their words in order.
+fn convert_tags(input: &str, tags: Vec<POSTag>, tag_set: TagSet) -> Vec<JSPOSTag> {
+    let mut cursor = 0;
+    tags.into_iter()
+        .map(|tag| {
+            let (start, end) = match text::find_word(input, &tag.word, cursor) {
+                Some((start, end)) => {
+                    cursor = end;
+                    let span = text::TextSpan::from_byte_range(input, start, end);
+                    (Some(span.start), Some(span.end))
+                }
+                None => (None, None),
+            };
+
+            JSPOSTag {
+                label: match tag_set {
+                    TagSet::Ptb => tag.label,
+                    TagSet::Ud => ptb_to_ud(&tag.label).to_string(),
+                },
+                word: tag.word,
+                score: tag.score,
+                start,
+                end,
+            }
+        })
+        .collect()
+}
+
+/// Tags the words of each input.
+pub fn predict_tags(model: &POSModel, init: &POSPredictInit) -> Vec<Vec<JSPOSTag>> {
+    model
+        .predict(&init.inputs)
+        .into_iter()
+        .zip(&init.inputs)
+        .map(|(tags, input)| convert_tags(input, tags, init.tag_set))
+        .collect()
+}
+
+#[ffi]
 #[no_mangle]
 pub extern "C" fn pos_predict(rid: usize, buf: *const u8, buf_len: usize) -> isize {
     exec(|| {
-        let inputs: Vec<String> =
-            serde_json::from_slice(unsafe { std::slice::from_raw_parts(buf, buf_len) })?;
+        let init: POSPredictInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse POS model input.")?;
 
         models::with_access(rid, |model| {
             let model = match model {
@@ -24,8 +130,8 @@ pub extern "C" fn pos_predict(rid: usize, buf: *const u8, buf_len: usize) -> isi
                 _ => return Err(anyhow::anyhow!("Expected POS Model at rid '{}'.", rid)),
             };
 
-            let outputs = model.predict(&inputs);
-            let outputs = serde_json::to_vec(&outputs).context("Failed to serialize POS tags.")?;
+            let outputs = codec::encode(&predict_tags(model, &init))
+                .context("Failed to serialize POS tags.")?;
             Ok(set_result(outputs) as isize)
         })
     })
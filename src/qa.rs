@@ -1,16 +1,51 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, set_result, text, Model, WeightStats};
 use anyhow::{anyhow, Context};
-use rust_bert::pipelines::question_answering::{self, QaInput, QuestionAnsweringModel};
+use rust_bert::pipelines::common::{ConfigOption, ModelType, TokenizerOption};
+use rust_bert::pipelines::question_answering::{
+    self, QaInput, QuestionAnsweringConfig, QuestionAnsweringOption,
+};
+use rust_tokenizers::{Offset, TokenIdsWithOffsets};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tch::{nn, no_grad, Device, Kind, Tensor};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QAModelInit {
+    pub max_seq_length: Option<usize>,
+    pub doc_stride: Option<usize>,
+    pub max_query_length: Option<usize>,
+    pub max_answer_length: Option<usize>,
+}
+
+impl QAModelInit {
+    pub fn create(&self) -> Result<QAModel, anyhow::Error> {
+        let defaults = QuestionAnsweringConfig::default();
+        let config = QuestionAnsweringConfig {
+            max_seq_length: self.max_seq_length.unwrap_or(defaults.max_seq_length),
+            doc_stride: self.doc_stride.unwrap_or(defaults.doc_stride),
+            max_query_length: self.max_query_length.unwrap_or(defaults.max_query_length),
+            max_answer_length: self.max_answer_length.unwrap_or(defaults.max_answer_length),
+            ..defaults
+        };
+
+        QAModel::new(config).context("Failed to create question answering model.")
+    }
+}
 
 /// Creates a QA model and returns the resource number.
+#[ffi]
 #[no_mangle]
-extern "C" fn create_qa_model() -> isize {
+pub extern "C" fn create_qa_model(init: *const u8, init_len: usize) -> isize {
     exec(|| {
-        let model = QuestionAnsweringModel::new(Default::default())
-            .context("Failed to create question answering model.")?;
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: QAModelInit =
+            codec::decode(bytes).context("Failed to parse question answering model config.")?;
 
-        models::allocate(Model::QuestionAnsweringModel(model)).map(|rid| rid as isize)
+        models::load(bytes, move || {
+            Ok(Model::QuestionAnsweringModel(init.create()?))
+        })
+        .map(|rid| rid as isize)
     })
 }
 
@@ -38,64 +73,659 @@ impl From<JSQaInput> for QaInput {
     }
 }
 
+/// An answer where `start` and `end` are UTF-16 offsets into the context.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JsQaAnswer {
-    score: f64,
-    start: usize,
-    end: usize,
-    answer: String,
+    pub score: f64,
+    pub start: usize,
+    pub end: usize,
+    pub answer: String,
+    /// See `PredictedAnswer::no_answer_score`.
+    pub no_answer_score: f64,
 }
 
-impl From<question_answering::Answer> for JsQaAnswer {
-    fn from(a: question_answering::Answer) -> JsQaAnswer {
+impl JsQaAnswer {
+    /// Converts the character offsets of the answer into UTF-16 offsets into `context`.
+    pub fn from_answer(a: PredictedAnswer, context: &str) -> JsQaAnswer {
         Self {
-            score: a.score,
-            start: a.start,
-            end: a.end,
-            answer: a.answer,
+            score: a.answer.score,
+            start: text::char_to_utf16_offset(context, a.answer.start),
+            end: text::char_to_utf16_offset(context, a.answer.end),
+            answer: a.answer.answer,
+            no_answer_score: a.no_answer_score,
         }
     }
 }
 
+/// An answer that `QAModel` predicts for a question.
+pub struct PredictedAnswer {
+    pub answer: question_answering::Answer,
+    /// The score of the null answer minus the score of this answer, where the scores are the sums
+    /// of the start and end logits. A question has no answers when this is higher than the
+    /// no-answer threshold for its best answer.
+    pub no_answer_score: f64,
+}
+
+/// A window of a context paired with a question, as it is given to the model.
+struct QaFeature {
+    /// The index of the question.
+    question: usize,
+    input_ids: Vec<i64>,
+    /// The character offsets into the context of the tokens of the window, which are the only
+    /// tokens that an answer can start or end at.
+    offsets: Vec<Option<Offset>>,
+}
+
+/// A span of a context that answers a question.
+struct Span {
+    answer: question_answering::Answer,
+    /// The sum of the start and end logits, which is compared with the null answer.
+    logit: f64,
+}
+
+/// An extractive question answering model. It runs the question answering head of `rust-bert`
+/// like its pipeline does, and also scores the null answer (the span of the classification
+/// token) that SQuAD2-style models predict when the context has no answer.
+pub struct QAModel {
+    tokenizer: TokenizerOption,
+    model: QuestionAnsweringOption,
+    var_store: nn::VarStore,
+    pad_id: i64,
+    sep_id: i64,
+    /// The number of special tokens added around a question and a window of the context.
+    special_tokens: usize,
+    max_seq_length: usize,
+    doc_stride: usize,
+    max_query_length: usize,
+    max_answer_length: usize,
+}
+
+impl QAModel {
+    pub fn new(config: QuestionAnsweringConfig) -> Result<Self, anyhow::Error> {
+        // The null answer is scored at the classification token, which XLNet puts at the end
+        if config.model_type == ModelType::XLNet {
+            return Err(anyhow!(
+                "XLNet question answering models are not supported."
+            ));
+        }
+        // Same as rust-bert, so that the windows always move forward
+        if config.max_seq_length < config.max_query_length + config.doc_stride + 24 {
+            return Err(anyhow!(
+                "Expected the max sequence length ({}) to be at least the max query length ({}) \
+                 plus the doc stride ({}) plus 24.",
+                config.max_seq_length,
+                config.max_query_length,
+                config.doc_stride
+            ));
+        }
+
+        let vocab = config
+            .vocab_resource
+            .get_local_path()
+            .context("Failed to get question answering vocab.")?;
+        let merges = match &config.merges_resource {
+            Some(merges) => Some(
+                merges
+                    .get_local_path()
+                    .context("Failed to get question answering merges.")?,
+            ),
+            None => None,
+        };
+        let tokenizer = TokenizerOption::from_file(
+            config.model_type,
+            vocab
+                .to_str()
+                .context("Invalid question answering vocab path.")?,
+            merges
+                .as_ref()
+                .map(|merges| {
+                    merges
+                        .to_str()
+                        .context("Invalid question answering merges path.")
+                })
+                .transpose()?,
+            config.lower_case,
+            config.strip_accents,
+            config.add_prefix_space,
+        )
+        .context("Failed to load question answering tokenizer.")?;
+        let pad_id = tokenizer
+            .get_pad_id()
+            .context("The question answering tokenizer has no pad token.")?;
+        let sep_id = tokenizer
+            .get_sep_id()
+            .context("The question answering tokenizer has no separator token.")?;
+        let special_tokens = tokenizer
+            .build_input_with_special_tokens(empty_ids(), Some(empty_ids()))
+            .token_ids
+            .len();
+
+        let mut model_config = ConfigOption::from_file(
+            config.model_type,
+            config
+                .config_resource
+                .get_local_path()
+                .context("Failed to get question answering config.")?,
+        );
+        // Like rust-bert's pipeline, the position embeddings are loaded from the weights.
+        if let ConfigOption::DistilBert(ref mut model_config) = model_config {
+            model_config.sinusoidal_pos_embds = false;
+        }
+        let weights = config
+            .model_resource
+            .get_local_path()
+            .context("Failed to get question answering weights.")?;
+        crate::reserve_weights(&weights)?;
+        let mut var_store = nn::VarStore::new(config.device);
+        let model =
+            QuestionAnsweringOption::new(config.model_type, &var_store.root(), &model_config)
+                .context("Failed to build question answering model.")?;
+        var_store
+            .load(weights)
+            .context("Failed to load question answering weights.")?;
+
+        Ok(Self {
+            tokenizer,
+            model,
+            var_store,
+            pad_id,
+            sep_id,
+            special_tokens,
+            max_seq_length: config.max_seq_length,
+            doc_stride: config.doc_stride,
+            max_query_length: config.max_query_length,
+            max_answer_length: config.max_answer_length,
+        })
+    }
+
+    pub fn tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    /// The maximum number of tokens of a question and a window of its context.
+    pub fn max_length(&self) -> usize {
+        self.max_seq_length
+    }
+
+    pub fn weights(&self) -> WeightStats {
+        WeightStats::of(&self.var_store)
+    }
+
+    fn encode(&self, text: &str) -> TokenIdsWithOffsets {
+        let tokens = self.tokenizer.tokenize_with_offsets(text);
+        TokenIdsWithOffsets {
+            ids: self.tokenizer.convert_tokens_to_ids(&tokens.tokens),
+            offsets: tokens.offsets,
+            reference_offsets: tokens.reference_offsets,
+            masks: tokens.masks,
+        }
+    }
+
+    /// Encodes the question, truncated to the max query length.
+    fn encode_question(&self, question: &str) -> TokenIdsWithOffsets {
+        let mut question = self.encode(question);
+        question.ids.truncate(self.max_query_length);
+        question.offsets.truncate(self.max_query_length);
+        question.reference_offsets.truncate(self.max_query_length);
+        question.masks.truncate(self.max_query_length);
+        question
+    }
+
+    /// Splits the context into windows that overlap by the doc stride. The windows leave room
+    /// for the longest question, so that every question about the context can use them.
+    fn windows(&self, context: &TokenIdsWithOffsets) -> Vec<TokenIdsWithOffsets> {
+        let max_window_length = self.max_seq_length - self.special_tokens - self.max_query_length;
+        let mut windows = Vec::new();
+        let mut start = 0;
+        while start < context.ids.len() {
+            let end = (start + max_window_length).min(context.ids.len());
+            windows.push(TokenIdsWithOffsets {
+                ids: context.ids[start..end].to_vec(),
+                offsets: context.offsets[start..end].to_vec(),
+                reference_offsets: context.reference_offsets[start..end].to_vec(),
+                masks: context.masks[start..end].to_vec(),
+            });
+
+            if end == context.ids.len() {
+                break;
+            }
+            start = end - self.doc_stride;
+        }
+        windows
+    }
+
+    /// Pairs the question with a window of its context.
+    fn feature(
+        &self,
+        question_index: usize,
+        question: &TokenIdsWithOffsets,
+        window: &TokenIdsWithOffsets,
+    ) -> QaFeature {
+        let encoded = self
+            .tokenizer
+            .build_input_with_special_tokens(question.clone(), Some(window.clone()));
+        let offsets = encoded
+            .token_offsets
+            .iter()
+            .zip(&encoded.segment_ids)
+            .zip(&encoded.token_ids)
+            .map(|((&offset, &segment), &id)| {
+                if segment > 0 && id != self.sep_id {
+                    offset
+                } else {
+                    None
+                }
+            })
+            .collect();
+        QaFeature {
+            question: question_index,
+            input_ids: encoded.token_ids,
+            offsets,
+        }
+    }
+
+    /// Runs the model over the features in batches and returns the start and end logits of
+    /// each feature.
+    fn forward(&self, features: &[QaFeature], batch_size: usize) -> Vec<(Vec<f32>, Vec<f32>)> {
+        let mut logits = Vec::with_capacity(features.len());
+        for batch in features.chunks(batch_size.max(1)) {
+            let batch_len = batch.iter().map(|f| f.input_ids.len()).max().unwrap_or(0);
+            let (input_ids, attention_mask): (Vec<Tensor>, Vec<Tensor>) = batch
+                .iter()
+                .map(|f| {
+                    let mut ids = f.input_ids.clone();
+                    let mut mask = vec![1i64; ids.len()];
+                    ids.resize(batch_len, self.pad_id);
+                    mask.resize(batch_len, 0);
+                    (Tensor::of_slice(&ids), Tensor::of_slice(&mask))
+                })
+                .unzip();
+            let input_ids = Tensor::stack(&input_ids, 0).to(self.var_store.device());
+            let attention_mask = Tensor::stack(&attention_mask, 0).to(self.var_store.device());
+
+            let (start, end) = no_grad(|| {
+                self.model
+                    .forward_t(Some(&input_ids), Some(&attention_mask), None, false)
+            });
+            let (start, end) = (to_vec(&start), to_vec(&end));
+            for (i, feature) in batch.iter().enumerate() {
+                let row = i * batch_len..i * batch_len + feature.input_ids.len();
+                logits.push((start[row.clone()].to_vec(), end[row].to_vec()));
+            }
+        }
+        logits
+    }
+
+    /// Finds the `top_k` spans of the window with the highest probability, where the
+    /// probabilities of the starts and ends are normalized over the tokens of the context.
+    fn decode(
+        &self,
+        feature: &QaFeature,
+        (start, end): &(Vec<f32>, Vec<f32>),
+        context: &str,
+        top_k: usize,
+    ) -> Vec<Span> {
+        let start_probabilities = softmax(start, &feature.offsets);
+        let end_probabilities = softmax(end, &feature.offsets);
+
+        let mut spans = Vec::new();
+        for (s, start_offset) in feature.offsets.iter().enumerate() {
+            let first = match start_offset {
+                Some(offset) => offset.begin as usize,
+                None => continue,
+            };
+            let last = (s + self.max_answer_length).min(feature.offsets.len());
+            for e in s..last {
+                if let Some(end_offset) = feature.offsets[e] {
+                    let end_char = end_offset.end as usize;
+                    spans.push(Span {
+                        answer: question_answering::Answer {
+                            score: start_probabilities[s] * end_probabilities[e],
+                            start: first,
+                            end: end_char,
+                            // Only the text of the spans that are kept is read from the context
+                            answer: String::new(),
+                        },
+                        logit: start[s] as f64 + end[e] as f64,
+                    });
+                }
+            }
+        }
+        spans.sort_by(|a, b| b.answer.score.total_cmp(&a.answer.score));
+        spans.truncate(top_k);
+        for span in &mut spans {
+            let answer = &mut span.answer;
+            answer.answer = context
+                .chars()
+                .skip(answer.start)
+                .take(answer.end.saturating_sub(answer.start))
+                .collect();
+        }
+        spans
+    }
+
+    /// Answers each question with the `top_k` spans of its context.
+    ///
+    /// With a no-answer threshold, a question has no answers when the score of the null answer
+    /// is higher than the score of the best span by more than the threshold, where the scores are
+    /// the sums of the start and end logits like in the SQuAD2 evaluation script.
+    pub fn predict(
+        &self,
+        inputs: &[QaInput],
+        top_k: usize,
+        batch_size: usize,
+        no_answer_threshold: Option<f64>,
+    ) -> Vec<Vec<PredictedAnswer>> {
+        // Questions about the same context share its windows
+        let mut contexts: Vec<&str> = Vec::new();
+        let mut context_indices: HashMap<&str, usize> = HashMap::new();
+        let questions = inputs
+            .iter()
+            .map(|input| {
+                let context = *context_indices
+                    .entry(input.context.as_str())
+                    .or_insert_with(|| {
+                        contexts.push(&input.context);
+                        contexts.len() - 1
+                    });
+                (context, input.question.as_str())
+            })
+            .collect::<Vec<_>>();
+
+        self.predict_questions(
+            &contexts,
+            &questions,
+            top_k,
+            batch_size,
+            no_answer_threshold,
+        )
+    }
+
+    /// Same as `predict`, where each question is given with the index of its context. Each
+    /// context is tokenized and split into windows once for all of its questions.
+    pub fn predict_questions(
+        &self,
+        contexts: &[&str],
+        questions: &[(usize, &str)],
+        top_k: usize,
+        batch_size: usize,
+        no_answer_threshold: Option<f64>,
+    ) -> Vec<Vec<PredictedAnswer>> {
+        let windows = contexts
+            .iter()
+            .map(|context| self.windows(&self.encode(context)))
+            .collect::<Vec<_>>();
+        let features = questions
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &(context, question))| {
+                let question = self.encode_question(question);
+                windows[context]
+                    .iter()
+                    .map(|window| self.feature(i, &question, window))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let logits = self.forward(&features, batch_size);
+
+        let mut spans: Vec<Vec<Span>> = questions.iter().map(|_| Vec::new()).collect();
+        // The lowest score of the null answer over the windows of each question
+        let mut null_scores = vec![f64::INFINITY; questions.len()];
+        for (feature, logits) in features.iter().zip(&logits) {
+            // The classification token is the first token of every model type that answers
+            // questions except for XLNet, which `new` rejects.
+            let null_score = logits.0[0] as f64 + logits.1[0] as f64;
+            null_scores[feature.question] = null_scores[feature.question].min(null_score);
+            let context = contexts[questions[feature.question].0];
+            spans[feature.question].extend(self.decode(feature, logits, context, top_k));
+        }
+
+        spans
+            .into_iter()
+            .zip(null_scores)
+            .map(|(mut spans, null_score)| {
+                let best = spans
+                    .iter()
+                    .map(|span| span.logit)
+                    .fold(f64::NEG_INFINITY, f64::max);
+                if matches!(no_answer_threshold, Some(threshold) if null_score - best > threshold) {
+                    return Vec::new();
+                }
+
+                spans.sort_by(|a, b| b.answer.score.total_cmp(&a.answer.score));
+                let mut answers: Vec<PredictedAnswer> = Vec::new();
+                for span in spans {
+                    if answers.len() == top_k {
+                        break;
+                    }
+                    if !answers.iter().any(|a| {
+                        a.answer.start == span.answer.start && a.answer.end == span.answer.end
+                    }) {
+                        answers.push(PredictedAnswer {
+                            answer: span.answer,
+                            no_answer_score: null_score - span.logit,
+                        });
+                    }
+                }
+                answers
+            })
+            .collect()
+    }
+}
+
+fn empty_ids() -> TokenIdsWithOffsets {
+    TokenIdsWithOffsets {
+        ids: Vec::new(),
+        offsets: Vec::new(),
+        reference_offsets: Vec::new(),
+        masks: Vec::new(),
+    }
+}
+
+/// Copies the logits of a batch to the CPU.
+fn to_vec(tensor: &Tensor) -> Vec<f32> {
+    let tensor = tensor
+        .to_kind(Kind::Float)
+        .to_device(Device::Cpu)
+        .contiguous();
+    let numel = tensor.numel();
+    let mut values = vec![0f32; numel];
+    tensor.copy_data(&mut values, numel);
+    values
+}
+
+/// Normalizes the logits of the tokens that have an offset into probabilities, where the other
+/// tokens have a probability of 0.
+fn softmax(logits: &[f32], offsets: &[Option<Offset>]) -> Vec<f64> {
+    let max = logits
+        .iter()
+        .zip(offsets)
+        .filter(|(_, offset)| offset.is_some())
+        .map(|(&logit, _)| logit as f64)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let exps = logits
+        .iter()
+        .zip(offsets)
+        .map(|(&logit, offset)| match offset {
+            Some(_) => (logit as f64 - max).exp(),
+            None => 0.0,
+        })
+        .collect::<Vec<_>>();
+    let sum = exps.iter().sum::<f64>();
+    exps.into_iter().map(|exp| exp / sum).collect()
+}
+
+/// Runs the question answering model. With a no-answer threshold, an empty group of answers
+/// means that the model predicts that the context has no answer (see `QAModel::predict`).
+pub fn predict_answers(
+    model: &QAModel,
+    inputs: Vec<QaInput>,
+    answers_len: usize,
+    batch_size: usize,
+    no_answer_threshold: Option<f64>,
+) -> Vec<Vec<JsQaAnswer>> {
+    model
+        .predict(&inputs, answers_len, batch_size, no_answer_threshold)
+        .into_iter()
+        .zip(&inputs)
+        .map(|(answers, input)| {
+            answers
+                .into_iter()
+                .map(|a| JsQaAnswer::from_answer(a, &input.context))
+                .collect()
+        })
+        .collect()
+}
+
+/// Calls `f` with the question answering model at the rid.
+pub(crate) fn with_qa_model<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+where
+    F: FnOnce(&QAModel) -> T,
+{
+    models::with_access(rid, |model| match model {
+        Model::QuestionAnsweringModel(model) => Ok(f(model)),
+        _ => Err(anyhow!(
+            "Expected question answering model at resource id {}.",
+            rid
+        )),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaQueryInit {
+    pub question_groups: Vec<JSQaInput>,
+    /// See `QAModel::predict`.
+    #[serde(default)]
+    pub no_answer_threshold: Option<f64>,
+}
+
+/// Answers each question with the top answers of its context.
+#[ffi]
 #[no_mangle]
-extern "C" fn qa_query(
+pub extern "C" fn qa_query(
     rid: usize,
-    inputs: *const u8,
-    inputs_len: usize,
+    init: *const u8,
+    init_len: usize,
     answers_len: usize,
     batch_size: usize,
 ) -> isize {
     exec(|| {
-        let inputs = unsafe { std::slice::from_raw_parts(inputs, inputs_len) };
-        let inputs: Vec<JSQaInput> = serde_json::from_slice(inputs)
-            .context("Failed to parse question answering model inputs.")?;
-        let inputs = inputs
+        let init = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: QaQueryInit =
+            codec::decode(init).context("Failed to parse question answering model inputs.")?;
+        let inputs = init
+            .question_groups
             .into_iter()
             .map(|i| QaInput::from(i))
             .collect::<Vec<_>>();
 
-        let answers = models::with_access(rid, move |model| {
-            let model = match model {
-                Model::QuestionAnsweringModel(model) => model,
-                _ => {
-                    return Err(anyhow!(
-                        "Expected question answering model at resource id {}.",
-                        rid
-                    ))
-                }
-            };
+        let js_answers = with_qa_model(rid, |model| {
+            predict_answers(
+                model,
+                inputs,
+                answers_len,
+                batch_size,
+                init.no_answer_threshold,
+            )
+        })?;
 
-            let res = model.predict(&inputs, answers_len as i64, batch_size);
+        let serialized = codec::encode(&js_answers).context("Failed to serialize answers.")?;
 
-            Ok(res)
-        })?;
+        Ok(set_result(serialized) as isize)
+    })
+}
 
-        let js_answers: Vec<Vec<JsQaAnswer>> = answers
-            .into_iter()
-            .map(|answers| answers.into_iter().map(|a| a.into()).collect())
-            .collect();
+/// A context with all of the questions to ask about it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JSQaContextInput {
+    pub context: String,
+    pub questions: Vec<String>,
+}
+
+/// Same as `predict_answers` except that each context is only given once for all of its
+/// questions, and is only tokenized and split into windows once. The answers are grouped by
+/// context and then by question.
+pub fn predict_context_answers(
+    model: &QAModel,
+    inputs: Vec<JSQaContextInput>,
+    answers_len: usize,
+    batch_size: usize,
+    no_answer_threshold: Option<f64>,
+) -> Vec<Vec<Vec<JsQaAnswer>>> {
+    let contexts = inputs
+        .iter()
+        .map(|i| i.context.as_str())
+        .collect::<Vec<_>>();
+    let questions = inputs
+        .iter()
+        .enumerate()
+        .flat_map(|(c, i)| i.questions.iter().map(move |q| (c, q.as_str())))
+        .collect::<Vec<_>>();
+
+    let mut answers = model
+        .predict_questions(
+            &contexts,
+            &questions,
+            answers_len,
+            batch_size,
+            no_answer_threshold,
+        )
+        .into_iter();
+    inputs
+        .iter()
+        .map(|input| {
+            answers
+                .by_ref()
+                .take(input.questions.len())
+                .map(|answers| {
+                    answers
+                        .into_iter()
+                        .map(|a| JsQaAnswer::from_answer(a, &input.context))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaContextQueryInit {
+    pub contexts: Vec<JSQaContextInput>,
+    /// See `QAModel::predict`.
+    #[serde(default)]
+    pub no_answer_threshold: Option<f64>,
+}
+
+/// Same as `qa_query` except that each context is only sent once for all of its questions. The
+/// answers are grouped by context and then by question.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn qa_query_contexts(
+    rid: usize,
+    init: *const u8,
+    init_len: usize,
+    answers_len: usize,
+    batch_size: usize,
+) -> isize {
+    exec(|| {
+        let init = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: QaContextQueryInit = codec::decode(init)
+            .context("Failed to parse question answering model context inputs.")?;
+
+        let grouped = with_qa_model(rid, |model| {
+            predict_context_answers(
+                model,
+                init.contexts,
+                answers_len,
+                batch_size,
+                init.no_answer_threshold,
+            )
+        })?;
 
-        let serialized = serde_json::to_vec(&js_answers).context("Failed to serialize answers.")?;
+        let serialized = codec::encode(&grouped).context("Failed to serialize answers.")?;
 
         Ok(set_result(serialized) as isize)
     })
@@ -0,0 +1,270 @@
+use crate::{
+    codec, exec, ffi, model_resources, predict_answers, set_result, with_qa_model, JsQaAnswer,
+    ModelResource, QAModel,
+};
+use anyhow::Context;
+use rust_bert::pipelines::question_answering::QaInput;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+#[derive(Debug, Deserialize)]
+pub struct JSPassage {
+    pub id: String,
+    pub text: String,
+}
+
+struct IndexedPassage {
+    passage: JSPassage,
+    term_freqs: HashMap<String, usize>,
+    len: usize,
+}
+
+/// A collection of passages indexed for BM25 retrieval.
+pub struct PassageCorpus {
+    passages: Vec<IndexedPassage>,
+    doc_freqs: HashMap<String, usize>,
+    avg_len: f64,
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+}
+
+impl PassageCorpus {
+    pub fn new(passages: Vec<JSPassage>) -> Self {
+        let mut doc_freqs: HashMap<String, usize> = HashMap::new();
+        let passages: Vec<IndexedPassage> = passages
+            .into_iter()
+            .map(|passage| {
+                let mut term_freqs: HashMap<String, usize> = HashMap::new();
+                let mut len = 0;
+                for term in terms(&passage.text) {
+                    *term_freqs.entry(term).or_default() += 1;
+                    len += 1;
+                }
+                for term in term_freqs.keys() {
+                    *doc_freqs.entry(term.clone()).or_default() += 1;
+                }
+                IndexedPassage {
+                    passage,
+                    term_freqs,
+                    len,
+                }
+            })
+            .collect();
+        let avg_len =
+            passages.iter().map(|p| p.len).sum::<usize>() as f64 / passages.len().max(1) as f64;
+
+        Self {
+            passages,
+            doc_freqs,
+            avg_len,
+        }
+    }
+
+    /// Gets the indices and BM25 scores of the `k` passages that match the query the best.
+    pub fn search(&self, query: &str, k: usize) -> Vec<(usize, f64)> {
+        let n = self.passages.len() as f64;
+        let mut query_terms = terms(query).collect::<Vec<_>>();
+        query_terms.sort();
+        query_terms.dedup();
+        if query_terms.is_empty() || self.passages.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: Vec<(usize, f64)> = self
+            .passages
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let score = query_terms
+                    .iter()
+                    .filter_map(|term| {
+                        let tf = *p.term_freqs.get(term)? as f64;
+                        let df = self.doc_freqs[term] as f64;
+                        let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        let norm = 1.0 - BM25_B + BM25_B * p.len as f64 / self.avg_len;
+                        Some(idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm))
+                    })
+                    .sum::<f64>();
+                (i, score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores.truncate(k);
+        scores
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_qa_corpus(buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let passages: Vec<JSPassage> =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse question answering corpus passages.")?;
+
+        model_resources::allocate(ModelResource::PassageCorpus(PassageCorpus::new(passages)))
+            .context("Failed to allocate question answering corpus.")
+            .map(|a| a as isize)
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaCorpusQueryInit {
+    pub questions: Vec<String>,
+    pub n_passages: usize,
+    pub n_top_answers: usize,
+    pub batch_size: usize,
+    /// See `QAModel::predict`.
+    #[serde(default)]
+    pub no_answer_threshold: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsQaCorpusAnswer {
+    pub passage_id: String,
+    /// The BM25 score of the passage for the question.
+    pub passage_score: f64,
+    #[serde(flatten)]
+    pub answer: JsQaAnswer,
+}
+
+impl PassageCorpus {
+    /// Retrieves the passages that match each question the best and returns the top answers
+    /// found in those passages.
+    pub fn query(&self, model: &QAModel, init: &QaCorpusQueryInit) -> Vec<Vec<JsQaCorpusAnswer>> {
+        // (question, passage) for every retrieved passage
+        let retrieved = init
+            .questions
+            .iter()
+            .enumerate()
+            .flat_map(|(q, question)| {
+                self.search(question, init.n_passages)
+                    .into_iter()
+                    .map(move |(i, score)| (q, &self.passages[i].passage, score))
+            })
+            .collect::<Vec<_>>();
+
+        let mut grouped: Vec<Vec<JsQaCorpusAnswer>> =
+            init.questions.iter().map(|_| Vec::new()).collect();
+        // No passage matches any of the questions
+        if retrieved.is_empty() {
+            return grouped;
+        }
+
+        let inputs = retrieved
+            .iter()
+            .map(|(q, passage, _)| QaInput {
+                question: init.questions[*q].clone(),
+                context: passage.text.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let answers = predict_answers(
+            model,
+            inputs,
+            init.n_top_answers,
+            init.batch_size,
+            init.no_answer_threshold,
+        );
+
+        for ((q, passage, passage_score), answers) in retrieved.into_iter().zip(answers) {
+            grouped[q].extend(answers.into_iter().map(|answer| JsQaCorpusAnswer {
+                passage_id: passage.id.clone(),
+                passage_score,
+                answer,
+            }));
+        }
+        for answers in grouped.iter_mut() {
+            answers.sort_by(|a, b| b.answer.score.total_cmp(&a.answer.score));
+            answers.truncate(init.n_top_answers);
+        }
+        grouped
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn qa_corpus_query(
+    model_rid: usize,
+    corpus_rid: usize,
+    buf: *const u8,
+    buf_len: usize,
+) -> isize {
+    exec(|| {
+        let init: QaCorpusQueryInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse question answering corpus query.")?;
+
+        let grouped = model_resources::with_access(corpus_rid, |resource| {
+            let corpus = match resource {
+                ModelResource::PassageCorpus(c) => &*c,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Expected question answering corpus at resource id '{}'.",
+                        corpus_rid
+                    ))
+                }
+            };
+
+            with_qa_model(model_rid, |model| corpus.query(model, &init))
+        })?;
+
+        let serialized = codec::encode(&grouped).context("Failed to serialize answers.")?;
+
+        Ok(set_result(serialized) as isize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(texts: &[&str]) -> PassageCorpus {
+        PassageCorpus::new(
+            texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| JSPassage {
+                    id: i.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn ranks_passages_by_bm25() {
+        let corpus = corpus(&[
+            "The cat sat on the mat.",
+            "Cats and dogs. The cat chased the dog, the cat won.",
+            "Nothing to see here.",
+        ]);
+
+        let results = corpus.search("CAT", 10);
+        assert_eq!(
+            results.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert!(results[0].1 > results[1].1);
+        assert_eq!(corpus.search("cat", 1).len(), 1);
+    }
+
+    #[test]
+    fn finds_nothing_without_matching_terms() {
+        let corpus = corpus(&["The cat sat on the mat."]);
+        assert!(corpus.search("bird", 10).is_empty());
+        assert!(corpus.search(" ,. ", 10).is_empty());
+        assert!(PassageCorpus::new(Vec::new()).search("cat", 10).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, Model};
+use anyhow::Context;
+use rust_bert::pipelines::sentence_embeddings::{
+    SentenceEmbeddingsConfig, SentenceEmbeddingsModel, SentenceEmbeddingsModelType,
+};
+use rust_bert::resources::LocalResource;
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Pooling {
+    Mean,
+    Cls,
+    Max,
+    MeanSqrtLen,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentenceEmbeddingsModelInit {
+    /// The directory of a local SBERT-style model. Defaults to `all-MiniLM-L12-v2` from the
+    /// Hugging Face hub.
+    pub model_path: Option<String>,
+    /// Overrides the pooling of the model's `1_Pooling/config.json`.
+    pub pooling: Option<Pooling>,
+}
+
+impl SentenceEmbeddingsModelInit {
+    pub fn create(&self) -> Result<SentenceEmbeddingsModel, anyhow::Error> {
+        let config = sentence_embeddings_config(
+            self.model_path.clone(),
+            SentenceEmbeddingsModelType::AllMiniLmL12V2,
+            self.pooling,
+        )?;
+        record_weights(&*config.transformer_weights_resource)?;
+        record_vocab(
+            config.transformer_type,
+            &*config.tokenizer_vocab_resource,
+            config.tokenizer_merges_resource.as_deref(),
+        )?;
+        SentenceEmbeddingsModel::new(config).context("Failed to create sentence embeddings model.")
+    }
+}
+
+/// Writes a copy of the pooling config with only the given pooling mode enabled and points the
+/// config at it. The copy is named after a hash of its content, so that creating (or reloading)
+/// models with the same config reuses one file instead of writing a new one each time.
+fn override_pooling(
+    config: &mut SentenceEmbeddingsConfig,
+    pooling: Pooling,
+) -> Result<(), anyhow::Error> {
+    let path = config.pooling_config_resource.get_local_path()?;
+    let mut pooling_config: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(&path).context("Failed to read sentence embeddings pooling config.")?,
+    )
+    .context("Failed to parse sentence embeddings pooling config.")?;
+
+    for (key, enabled) in [
+        ("pooling_mode_mean_tokens", matches!(pooling, Pooling::Mean)),
+        ("pooling_mode_cls_token", matches!(pooling, Pooling::Cls)),
+        ("pooling_mode_max_tokens", matches!(pooling, Pooling::Max)),
+        (
+            "pooling_mode_mean_sqrt_len_tokens",
+            matches!(pooling, Pooling::MeanSqrtLen),
+        ),
+    ] {
+        pooling_config[key] = serde_json::Value::Bool(enabled);
+    }
+
+    let bytes = serde_json::to_vec(&pooling_config)?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let path = std::env::temp_dir().join(format!("bertml-pooling-{:016x}.json", hasher.finish()));
+    if !path.exists() {
+        // Written next to the final path and renamed, so that a model that is created at the same
+        // time never reads a partial file
+        let temp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        std::fs::write(&temp, &bytes)
+            .and_then(|_| std::fs::rename(&temp, &path))
+            .context("Failed to write sentence embeddings pooling config.")?;
+    }
+    config.pooling_config_resource = Box::new(LocalResource::from(path));
+    Ok(())
+}
+
+/// Creates the config of a local model directory, or of the remote model when there is no path.
+pub fn sentence_embeddings_config(
+    model_path: Option<String>,
+    remote: SentenceEmbeddingsModelType,
+    pooling: Option<Pooling>,
+) -> Result<SentenceEmbeddingsConfig, anyhow::Error> {
+    let mut config = match model_path {
+        Some(path) => SentenceEmbeddingsConfig::from(path),
+        None => SentenceEmbeddingsConfig::from(remote),
+    };
+    if let Some(pooling) = pooling {
+        override_pooling(&mut config, pooling)?;
+    }
+    Ok(config)
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_sentence_embeddings_model(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: SentenceEmbeddingsModelInit =
+            codec::decode(bytes).context("Failed to parse sentence embeddings model config.")?;
+
+        models::load(bytes, move || {
+            Ok(Model::SentenceEmbeddingsModel(init.create()?))
+        })
+        .map(|rid| rid as isize)
+    })
+}
+
+#[derive(Deserialize)]
+pub struct SentenceEmbeddingsInit {
+    pub inputs: Vec<String>,
+    /// Whether the embeddings should be scaled to a length of 1.
+    #[serde(default)]
+    pub normalize: bool,
+}
+
+/// Scales the embedding to a length of 1.
+pub fn normalize(embedding: &mut [f32]) {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        embedding.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Encodes the inputs, one embedding per input.
+pub fn embed_sentences(
+    model: &SentenceEmbeddingsModel,
+    inputs: &[String],
+    normalized: bool,
+) -> Result<Vec<Vec<f32>>, anyhow::Error> {
+    // rust-bert can't encode an empty batch
+    if inputs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut embeddings = model
+        .encode(inputs)
+        .context("Failed to encode sentence embeddings.")?;
+
+    if normalized {
+        embeddings.iter_mut().for_each(|e| normalize(e));
+    }
+    Ok(embeddings)
+}
+
+/// Encodes the inputs with the sentence embeddings model at the rid.
+pub fn encode_sentences(
+    rid: usize,
+    inputs: &[String],
+    normalized: bool,
+) -> Result<Vec<Vec<f32>>, anyhow::Error> {
+    models::with_access(rid, |model| match model {
+        Model::SentenceEmbeddingsModel(m) => embed_sentences(m, inputs, normalized),
+        _ => Err(anyhow::anyhow!(
+            "Expected sentence embeddings model at rid '{}'.",
+            rid
+        )),
+    })
+}
+
+/// Encodes the inputs and sets the result to the embeddings as contiguous little-endian `f32`s,
+/// one row per input.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn sentence_embeddings_encode(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: SentenceEmbeddingsInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse sentence embeddings input.")?;
+
+        let embeddings = encode_sentences(rid, &init.inputs, init.normalize)?;
+
+        Ok(set_result(
+            embeddings
+                .iter()
+                .flatten()
+                .flat_map(|x| x.to_le_bytes())
+                .collect(),
+        ) as isize)
+    })
+}
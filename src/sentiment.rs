@@ -1,21 +1,33 @@
-use crate::{exec, models, set_result, Model};
+use crate::text::{self, TextSpan};
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, Model};
 use anyhow::Context;
-use rust_bert::pipelines::sentiment::{self, SentimentModel};
+use rust_bert::pipelines::sentiment::{self, SentimentConfig, SentimentModel};
 use serde::{Deserialize, Serialize};
 
+#[ffi]
 #[no_mangle]
-extern "C" fn create_sentiment_model() -> isize {
+pub extern "C" fn create_sentiment_model() -> isize {
     exec(|| {
-        let model =
-            SentimentModel::new(Default::default()).context("Failed to load sentiment model.")?;
-        models::allocate(Model::SentimentModel(model)).map(|rid| rid as isize)
+        models::load(&[], || {
+            let config = SentimentConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model = SentimentModel::new(config).context("Failed to load sentiment model.")?;
+            Ok(Model::SentimentModel(model))
+        })
+        .map(|rid| rid as isize)
     })
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct JSSentiment {
-    polarity: u8,
-    score: f64,
+    /// 0 for negative and 1 for positive.
+    pub polarity: u8,
+    pub score: f64,
 }
 
 impl From<sentiment::Sentiment> for JSSentiment {
@@ -30,30 +42,148 @@ impl From<sentiment::Sentiment> for JSSentiment {
     }
 }
 
+/// Predicts the sentiment of each of the inputs.
+pub fn predict_sentiments(model: &SentimentModel, inputs: &[String]) -> Vec<JSSentiment> {
+    let inputs = inputs.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    model
+        .predict(&inputs)
+        .into_iter()
+        .map(|s| s.into())
+        .collect()
+}
+
+fn with_sentiment_model<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+where
+    F: FnOnce(&SentimentModel) -> T,
+{
+    models::with_access(rid, |model| match model {
+        Model::SentimentModel(m) => Ok(f(m)),
+        _ => Err(anyhow::anyhow!(
+            "Expected Sentiment Model at resource id {}.",
+            rid
+        )),
+    })
+}
+
+#[ffi]
 #[no_mangle]
-extern "C" fn sentiment_predict(rid: usize, input: *const u8, input_len: usize) -> isize {
+pub extern "C" fn sentiment_predict(rid: usize, input: *const u8, input_len: usize) -> isize {
     exec(|| {
-        let input: Vec<&str> =
-            serde_json::from_slice(unsafe { std::slice::from_raw_parts(input, input_len) })
+        let input: Vec<String> =
+            codec::decode(unsafe { std::slice::from_raw_parts(input, input_len) })
                 .context("Failed to parse sentiment model input.")?;
-        let sentiments = models::with_access(rid, move |model| {
-            let model = match model {
-                Model::SentimentModel(m) => m,
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "Expected Sentiment Model at resource id {}.",
-                        rid
-                    ))
-                }
-            };
-
-            Ok(model.predict(input.as_slice()))
-        })?;
-
-        let sentiments: Vec<JSSentiment> = sentiments.into_iter().map(|s| s.into()).collect();
-        let json =
-            serde_json::to_vec(&sentiments).context("Failed to serialize sentiment model data.")?;
-
-        Ok(set_result(json) as isize)
+        let sentiments = with_sentiment_model(rid, |model| predict_sentiments(model, &input))?;
+
+        let serialized =
+            codec::encode(&sentiments).context("Failed to serialize sentiment model data.")?;
+
+        Ok(set_result(serialized) as isize)
+    })
+}
+
+#[derive(Serialize)]
+pub struct JSSentenceSentiment {
+    #[serde(flatten)]
+    pub span: TextSpan,
+    #[serde(flatten)]
+    pub sentiment: JSSentiment,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JSDocumentSentiment {
+    pub sentences: Vec<JSSentenceSentiment>,
+    /// The mean of the sentence scores, where negative sentences count as negative scores.
+    pub mean: f64,
+    /// Same as `mean` but each sentence is weighted by its length.
+    pub weighted_mean: f64,
+    /// The index of the most negative sentence, if any sentence is negative.
+    pub most_negative: Option<usize>,
+}
+
+fn signed_score(s: &sentiment::Sentiment) -> f64 {
+    match s.polarity {
+        sentiment::SentimentPolarity::Negative => -s.score,
+        _ => s.score,
+    }
+}
+
+/// Splits each of the documents into sentences and predicts the sentiment of each sentence.
+pub fn predict_documents(model: &SentimentModel, documents: &[String]) -> Vec<JSDocumentSentiment> {
+    let sentences: Vec<Vec<TextSpan>> = documents
+        .iter()
+        .map(|document| {
+            text::split_sentences(document)
+                .into_iter()
+                .map(|(start, end)| TextSpan::from_byte_range(document, start, end))
+                .collect()
+        })
+        .collect();
+
+    let inputs = sentences
+        .iter()
+        .flatten()
+        .map(|span| span.text.as_str())
+        .collect::<Vec<_>>();
+    // rust-bert can't predict an empty batch
+    let mut sentiments = if inputs.is_empty() {
+        Vec::new()
+    } else {
+        model.predict(inputs.as_slice())
+    }
+    .into_iter();
+
+    sentences
+        .into_iter()
+        .map(|spans| {
+            let sentiments: Vec<sentiment::Sentiment> =
+                sentiments.by_ref().take(spans.len()).collect();
+            let scores: Vec<f64> = sentiments.iter().map(signed_score).collect();
+            let lengths: Vec<f64> = spans.iter().map(|s| (s.end - s.start) as f64).collect();
+
+            let count = scores.len().max(1) as f64;
+            let total_length = lengths.iter().sum::<f64>().max(1.0);
+
+            JSDocumentSentiment {
+                mean: scores.iter().sum::<f64>() / count,
+                weighted_mean: scores.iter().zip(&lengths).map(|(s, l)| s * l).sum::<f64>()
+                    / total_length,
+                most_negative: scores
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, score)| **score < 0.0)
+                    .min_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(i, _)| i),
+                sentences: spans
+                    .into_iter()
+                    .zip(sentiments)
+                    .map(|(span, s)| JSSentenceSentiment {
+                        span,
+                        sentiment: s.into(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn sentiment_predict_document(
+    rid: usize,
+    input: *const u8,
+    input_len: usize,
+) -> isize {
+    exec(|| {
+        let documents: Vec<String> =
+            codec::decode(unsafe { std::slice::from_raw_parts(input, input_len) })
+                .context("Failed to parse sentiment model document input.")?;
+
+        let documents = with_sentiment_model(rid, |model| predict_documents(model, &documents))?;
+
+        let serialized = codec::encode(&documents)
+            .context("Failed to serialize sentiment model document data.")?;
+
+        Ok(set_result(serialized) as isize)
     })
 }
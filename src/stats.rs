@@ -0,0 +1,123 @@
+use crate::{codec, exec, ffi, models, set_result, Allocation, Model};
+use anyhow::Context;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The size and usage of an allocated model.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStats {
+    pub rid: usize,
+    pub kind: &'static str,
+    /// Whether the model is loaded, or was evicted to stay within the memory budget and will be
+    /// reloaded on its next use.
+    pub loaded: bool,
+    /// The number of parameters, from the var store of the models whose weights bertml owns and
+    /// from the weight files of the `rust-bert` pipelines (see `record_weights`).
+    pub parameters: Option<u64>,
+    /// The memory of the weights, which is the size of the weight files for the `rust-bert`
+    /// pipelines (see `record_weights`).
+    pub weight_bytes: Option<u64>,
+    /// How much the resident memory of the process grew while the model was last loaded, which
+    /// includes its weights and tokenizer when they are loaded on the CPU.
+    pub loaded_bytes: Option<u64>,
+    /// `weight_bytes` when it is known and `loaded_bytes` otherwise, which is what the memory
+    /// budget counts.
+    pub memory_bytes: Option<u64>,
+    /// The vocab size of the tokenizer, see `record_vocab` for the `rust-bert` pipelines.
+    pub vocab_size: Option<usize>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    /// Milliseconds since the Unix epoch.
+    pub last_used_at: u64,
+    /// The number of inference calls that used the model, which doesn't count lookups like
+    /// tokenizing or getting the max length.
+    pub calls: u64,
+    /// The number of times that the model was evicted.
+    pub evictions: u64,
+}
+
+impl ModelStats {
+    pub fn new(rid: usize, allocation: &Allocation<Model>) -> Self {
+        Self {
+            rid,
+            kind: allocation.kind,
+            loaded: allocation.is_loaded(),
+            parameters: allocation.parameters,
+            weight_bytes: allocation.weight_bytes,
+            loaded_bytes: allocation.loaded_bytes,
+            memory_bytes: allocation.memory_bytes,
+            vocab_size: allocation.vocab_size,
+            created_at: unix_millis(allocation.created_at),
+            last_used_at: unix_millis(allocation.last_used_at),
+            calls: allocation.calls,
+            evictions: allocation.evictions,
+        }
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// The totals of every allocated model, where the parameters and memory only count the loaded
+/// models that they are known for.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStatsTotal {
+    pub models: usize,
+    pub loaded: usize,
+    pub parameters: u64,
+    pub memory_bytes: u64,
+    pub calls: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStatsReport {
+    pub models: Vec<ModelStats>,
+    pub total: ModelStatsTotal,
+    /// The bytes that the loaded models may take up, see `set_model_memory_budget`.
+    pub memory_budget: Option<u64>,
+}
+
+/// Gets the stats of every allocated model, in the order of their rids.
+pub fn model_stats() -> ModelStatsReport {
+    let models = models::map_all(ModelStats::new);
+    let total = models
+        .iter()
+        .fold(ModelStatsTotal::default(), |total, stats| ModelStatsTotal {
+            models: total.models + 1,
+            loaded: total.loaded + stats.loaded as usize,
+            parameters: total.parameters
+                + if stats.loaded {
+                    stats.parameters.unwrap_or(0)
+                } else {
+                    0
+                },
+            memory_bytes: total.memory_bytes
+                + if stats.loaded {
+                    stats.memory_bytes.unwrap_or(0)
+                } else {
+                    0
+                },
+            calls: total.calls + stats.calls,
+        });
+    ModelStatsReport {
+        models,
+        total,
+        memory_budget: models::memory_budget(),
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn get_model_stats() -> isize {
+    exec(|| {
+        Ok(
+            set_result(codec::encode(&model_stats()).context("Failed to serialize model stats.")?)
+                as isize,
+        )
+    })
+}
@@ -1,22 +1,38 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, Model};
 use anyhow::Context;
-use rust_bert::pipelines::summarization::SummarizationModel;
+use rust_bert::pipelines::summarization::{SummarizationConfig, SummarizationModel};
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn create_summarization_model() -> isize {
     exec(|| {
-        let model = SummarizationModel::new(Default::default())
-            .context("Failed to create summarization model.")?;
-
-        models::allocate(Model::SummarizationModel(model)).map(|a| a as isize)
+        models::load(&[], || {
+            let config = SummarizationConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model =
+                SummarizationModel::new(config).context("Failed to create summarization model.")?;
+            Ok(Model::SummarizationModel(model))
+        })
+        .map(|a| a as isize)
     })
 }
 
+/// Summarizes each of the inputs.
+pub fn summarize(model: &SummarizationModel, inputs: &[String]) -> Vec<String> {
+    model.summarize(inputs)
+}
+
+#[ffi]
 #[no_mangle]
 pub extern "C" fn summarization_summarize(rid: usize, buf: *const u8, buf_len: usize) -> isize {
     exec(|| {
         let inputs: Vec<String> =
-            serde_json::from_slice(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
                 .context("Failed to deserialize summarization input.")?;
 
         let outputs = models::with_access(rid, |model| {
@@ -30,12 +46,11 @@ pub extern "C" fn summarization_summarize(rid: usize, buf: *const u8, buf_len: u
                 }
             };
 
-            Ok(model.summarize(&inputs))
+            Ok(summarize(model, &inputs))
         })?;
 
         Ok(set_result(
-            serde_json::to_vec(&outputs)
-                .context("Failed to serialize summarization model output.")?,
+            codec::encode(&outputs).context("Failed to serialize summarization model output.")?,
         ) as isize)
     })
 }
@@ -0,0 +1,53 @@
+use crate::{exec, ffi, set_result};
+use serde::Serialize;
+
+/// A type that can be passed to or returned from an exported function, named like the native
+/// types of `Deno.dlopen`.
+pub trait FfiType {
+    const NAME: &'static str;
+}
+
+macro_rules! ffi_types {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(impl FfiType for $ty {
+            const NAME: &'static str = $name;
+        })*
+    };
+}
+
+ffi_types! {
+    () => "void",
+    u8 => "u8",
+    u32 => "u32",
+    usize => "usize",
+    isize => "isize",
+    f64 => "f64",
+    *const u8 => "buffer",
+    *mut u8 => "buffer",
+}
+
+/// An exported function of the library, added to the manifest by `#[ffi]`.
+#[derive(Debug, Serialize)]
+pub struct Symbol {
+    pub name: &'static str,
+    pub parameters: &'static [&'static str],
+    pub result: &'static str,
+}
+
+inventory::collect!(Symbol);
+
+/// Gets every exported function of the library, sorted by name.
+pub fn symbols() -> Vec<&'static Symbol> {
+    let mut symbols = inventory::iter::<Symbol>.into_iter().collect::<Vec<_>>();
+    symbols.sort_by_key(|symbol| symbol.name);
+    symbols
+}
+
+/// Sets the result to the symbol manifest, which is always JSON so that it can be read before
+/// the codec is known. `generate_symbols.ts` generates the symbol table of the Deno bindings
+/// from it.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn bertml_symbols() -> isize {
+    exec(|| Ok(set_result(serde_json::to_vec(&symbols())?) as isize))
+}
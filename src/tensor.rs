@@ -0,0 +1,154 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tch::{Device, Kind, Tensor};
+
+/// How a call that supports binary results encodes its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResultFormat {
+    #[default]
+    Json,
+    /// A `TensorFrame`.
+    Tensor,
+}
+
+/// The element type of a tensor in a frame, always little-endian.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DType {
+    F32,
+    I64,
+}
+
+/// The name, shape and position of a tensor in a frame.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TensorInfo {
+    name: String,
+    dtype: DType,
+    shape: Vec<i64>,
+    /// The byte offset of the tensor in the data section.
+    offset: usize,
+    /// The number of bytes of the tensor.
+    byte_length: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct FrameHeader<M: Serialize> {
+    tensors: Vec<TensorInfo>,
+    metadata: M,
+}
+
+/// A set of named tensors that is sent across the FFI boundary as one buffer:
+///
+/// - the byte length of the header (`u32`, little-endian)
+/// - the header as JSON: `{ "tensors": [TensorInfo], "metadata": ... }`
+/// - padding to a multiple of 8 bytes
+/// - the data section, with the row-major elements of each tensor at its offset, which is a
+///   multiple of 8
+#[derive(Default)]
+pub struct TensorFrame {
+    tensors: Vec<TensorInfo>,
+    data: Vec<u8>,
+}
+
+impl TensorFrame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push_bytes(
+        &mut self,
+        name: &str,
+        dtype: DType,
+        shape: Vec<i64>,
+        bytes: impl Iterator<Item = u8>,
+    ) {
+        self.data.resize((self.data.len() + 7) / 8 * 8, 0);
+        let offset = self.data.len();
+        self.data.extend(bytes);
+        self.tensors.push(TensorInfo {
+            name: name.to_string(),
+            dtype,
+            shape,
+            offset,
+            byte_length: self.data.len() - offset,
+        });
+    }
+
+    /// Appends the `f32`s as a tensor with the shape.
+    pub fn push_f32(&mut self, name: &str, shape: Vec<i64>, values: &[f32]) {
+        let bytes = values.iter().flat_map(|x| x.to_le_bytes());
+        self.push_bytes(name, DType::F32, shape, bytes);
+    }
+
+    /// Appends the `i64`s as a tensor with the shape.
+    pub fn push_i64(&mut self, name: &str, shape: Vec<i64>, values: &[i64]) {
+        let bytes = values.iter().flat_map(|x| x.to_le_bytes());
+        self.push_bytes(name, DType::I64, shape, bytes);
+    }
+
+    /// Copies the tensor to the CPU as `f32`s and appends it to the frame.
+    pub fn push(&mut self, name: &str, tensor: &Tensor) {
+        let tensor = tensor
+            .to_kind(Kind::Float)
+            .to_device(Device::Cpu)
+            .contiguous();
+        let numel = tensor.numel();
+        let mut values = vec![0f32; numel];
+        tensor.copy_data(&mut values, numel);
+        self.push_f32(name, tensor.size(), &values);
+    }
+
+    /// Serializes the frame with the metadata in its header.
+    pub fn into_bytes<M: Serialize>(self, metadata: M) -> Result<Vec<u8>, anyhow::Error> {
+        let header = serde_json::to_vec(&FrameHeader {
+            tensors: self.tensors,
+            metadata,
+        })
+        .context("Failed to serialize tensor frame header.")?;
+
+        let padding = (8 - (4 + header.len()) % 8) % 8;
+        let mut bytes = Vec::with_capacity(4 + header.len() + padding + self.data.len());
+        bytes.extend((header.len() as u32).to_le_bytes());
+        bytes.extend(header);
+        bytes.resize(bytes.len() + padding, b' ');
+        bytes.extend(self.data);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_layout() {
+        let mut frame = TensorFrame::new();
+        frame.push_f32("scores", vec![1, 3], &[1.0, 2.0, 3.0]);
+        frame.push_i64("ids", vec![2], &[7, -1]);
+        let bytes = frame.into_bytes(vec!["label"]).unwrap();
+
+        let header_len = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let header: serde_json::Value = serde_json::from_slice(&bytes[4..4 + header_len]).unwrap();
+        let data_start = (4 + header_len + 7) / 8 * 8;
+        let data = &bytes[data_start..];
+        assert_eq!(header["metadata"], serde_json::json!(["label"]));
+
+        let scores = &header["tensors"][0];
+        assert_eq!(scores["name"], "scores");
+        assert_eq!(scores["dtype"], "f32");
+        assert_eq!(scores["shape"], serde_json::json!([1, 3]));
+        assert_eq!(scores["offset"], 0);
+        assert_eq!(scores["byteLength"], 12);
+        assert_eq!(f32::from_le_bytes(data[8..12].try_into().unwrap()), 3.0);
+
+        // Every tensor starts at a multiple of 8
+        let ids = &header["tensors"][1];
+        assert_eq!(ids["dtype"], "i64");
+        assert_eq!(ids["offset"], 16);
+        assert_eq!(ids["byteLength"], 16);
+        assert_eq!(i64::from_le_bytes(data[24..32].try_into().unwrap()), -1);
+        assert_eq!(data.len(), 32);
+    }
+}
@@ -0,0 +1,190 @@
+use serde::Serialize;
+
+const SENTENCE_TERMINATORS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+const CLOSING_PUNCTUATION: &[char] = &['"', '\'', ')', ']', '”', '’', '»'];
+
+/// A slice of an input string. The offsets are in UTF-16 code units so that they line up with
+/// JavaScript string indices.
+#[derive(Debug, Clone, Serialize)]
+pub struct TextSpan {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TextSpan {
+    /// Creates a span from byte offsets into `source`.
+    pub fn from_byte_range(source: &str, start: usize, end: usize) -> Self {
+        let start_utf16 = utf16_len(&source[..start]);
+        Self {
+            text: source[start..end].to_string(),
+            start: start_utf16,
+            end: start_utf16 + utf16_len(&source[start..end]),
+        }
+    }
+}
+
+/// Gets the length of the string in UTF-16 code units.
+pub fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+}
+
+/// Converts an offset in characters (Unicode scalar values) into an offset in UTF-16 code units.
+pub fn char_to_utf16_offset(s: &str, offset: usize) -> usize {
+    s.chars().take(offset).map(char::len_utf16).sum()
+}
+
+/// Gets the UTF-16 offset of every character offset (Unicode scalar values) of the string, up to
+/// and including its length, to convert many offsets in one pass.
+pub fn utf16_offsets(s: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(s.len() + 1);
+    offsets.push(0);
+    for c in s.chars() {
+        offsets.push(offsets[offsets.len() - 1] + c.len_utf16());
+    }
+    offsets
+}
+
+/// Splits the text into sentences and returns the byte range of each sentence with the
+/// surrounding whitespace trimmed. Sentences end at terminal punctuation followed by whitespace
+/// and at line breaks.
+pub fn split_sentences(text: &str) -> Vec<(usize, usize)> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let end = if c == '\n' {
+            i
+        } else if SENTENCE_TERMINATORS.contains(&c) {
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if !SENTENCE_TERMINATORS.contains(&next) && !CLOSING_PUNCTUATION.contains(&next) {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            match chars.peek() {
+                Some((_, next)) if !next.is_whitespace() => continue,
+                _ => end,
+            }
+        } else {
+            continue;
+        };
+
+        push_trimmed(text, start, end, &mut sentences);
+        start = end;
+    }
+    push_trimmed(text, start, text.len(), &mut sentences);
+
+    sentences
+}
+
+fn push_trimmed(text: &str, start: usize, end: usize, ranges: &mut Vec<(usize, usize)>) {
+    let slice = &text[start..end];
+    let start = start + (slice.len() - slice.trim_start().len());
+    let end = end - (slice.len() - slice.trim_end().len());
+    if start < end {
+        ranges.push((start, end));
+    }
+}
+
+/// Finds the next occurrence of the word at or after the byte offset `from`, ignoring case, and
+/// returns its byte range.
+pub fn find_word(text: &str, word: &str, from: usize) -> Option<(usize, usize)> {
+    let word: Vec<char> = word.chars().flat_map(char::to_lowercase).collect();
+    if word.is_empty() {
+        return None;
+    }
+
+    text[from..].char_indices().find_map(|(i, _)| {
+        let start = from + i;
+        let mut matched = 0;
+        for (j, c) in text[start..].char_indices() {
+            for c in c.to_lowercase() {
+                if matched == word.len() || word[matched] != c {
+                    return None;
+                }
+                matched += 1;
+            }
+            if matched == word.len() {
+                return Some((start, start + j + c.len_utf8()));
+            }
+        }
+        None
+    })
+}
+
+/// Checks whether the phrase occurs in the text as a whole word (or words), ignoring case.
+pub fn contains_phrase(text: &str, phrase: &str) -> bool {
+    let text = text.to_lowercase();
+    let phrase = phrase.trim().to_lowercase();
+    if phrase.is_empty() {
+        return false;
+    }
+
+    text.match_indices(&phrase).any(|(i, m)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + m.len()..].chars().next();
+        !before.map_or(false, char::is_alphanumeric) && !after.map_or(false, char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<&str> {
+        split_sentences(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn splits_sentences_at_terminators_and_line_breaks() {
+        assert_eq!(
+            sentences("Hello there. How are you?!  Fine.\nNext line"),
+            vec!["Hello there.", "How are you?!", "Fine.", "Next line"]
+        );
+        assert_eq!(
+            sentences("He said \"stop.\" Then left."),
+            vec!["He said \"stop.\"", "Then left."]
+        );
+        assert_eq!(
+            sentences("Version 1.2 is out."),
+            vec!["Version 1.2 is out."]
+        );
+        assert!(sentences("  \n ").is_empty());
+    }
+
+    #[test]
+    fn finds_words_ignoring_case() {
+        let text = "The Café and the café.";
+        assert_eq!(find_word(text, "café", 0), Some((4, 9)));
+        assert_eq!(find_word(text, "CAFÉ", 5), Some((18, 23)));
+        assert_eq!(find_word(text, "tea", 0), None);
+        assert_eq!(find_word(text, "", 0), None);
+    }
+
+    #[test]
+    fn offsets_are_in_utf16_code_units() {
+        let text = "a😀b é";
+        assert_eq!(utf16_len(text), 6);
+        assert_eq!(char_to_utf16_offset(text, 2), 3);
+        assert_eq!(utf16_offsets(text), vec![0, 1, 3, 4, 5, 6]);
+
+        let start = text.find('b').unwrap();
+        let span = TextSpan::from_byte_range(text, start, text.len());
+        assert_eq!(span.text, "b é");
+        assert_eq!((span.start, span.end), (3, 6));
+    }
+
+    #[test]
+    fn contains_whole_phrases_only() {
+        assert!(contains_phrase("The battery life is great", "Battery Life"));
+        assert!(!contains_phrase("The batteries are great", "battery"));
+        assert!(!contains_phrase("anything", " "));
+    }
+}
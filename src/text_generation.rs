@@ -1,29 +1,45 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, record_vocab, record_weights, set_result, Model};
 use anyhow::Context;
-use rust_bert::pipelines::text_generation::TextGenerationModel;
+use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
 use serde::Deserialize;
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn create_text_generation_model() -> isize {
     exec(|| {
-        let model = TextGenerationModel::new(Default::default())
-            .context("Failed to create text generation model.")?;
-
-        models::allocate(Model::TextGenerationModel(model)).map(|a| a as isize)
+        models::load(&[], || {
+            let config = TextGenerationConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model = TextGenerationModel::new(config)
+                .context("Failed to create text generation model.")?;
+            Ok(Model::TextGenerationModel(model))
+        })
+        .map(|a| a as isize)
     })
 }
 
 #[derive(Deserialize)]
 pub struct TextGenerationInit {
-    inputs: Vec<String>,
-    prefix: Option<String>,
+    pub inputs: Vec<String>,
+    pub prefix: Option<String>,
+}
+
+/// Generates a continuation of each of the inputs.
+pub fn generate(model: &TextGenerationModel, init: &TextGenerationInit) -> Vec<String> {
+    model.generate(&init.inputs, init.prefix.as_deref())
 }
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn text_generation_generate(rid: usize, buf: *const u8, buf_len: usize) -> isize {
     exec(|| {
         let init: TextGenerationInit =
-            serde_json::from_slice(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
                 .context("Failed to parse text generation model init.")?;
 
         models::with_access(rid, |model| {
@@ -36,10 +52,10 @@ pub extern "C" fn text_generation_generate(rid: usize, buf: *const u8, buf_len:
                     ))
                 }
             };
-            let data = model.generate(&init.inputs, init.prefix.as_deref());
+            let data = generate(model, &init);
 
             Ok(set_result(
-                serde_json::to_vec(&data).context("Failed to serialize text generation data.")?,
+                codec::encode(&data).context("Failed to serialize text generation data.")?,
             ) as isize)
         })
     })
@@ -0,0 +1,305 @@
+use crate::{codec, exec, ffi, models, set_result, text, Model};
+use anyhow::Context;
+use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+use rust_tokenizers::tokenizer::Tokenizer;
+use rust_tokenizers::vocab::Vocab;
+use rust_tokenizers::TokenIdsWithOffsets;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_LENGTH: usize = 512;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenizerKind {
+    Bert,
+    DistilBert,
+    Roberta,
+    Gpt2,
+    Albert,
+    XlmRoberta,
+}
+
+impl From<TokenizerKind> for ModelType {
+    fn from(kind: TokenizerKind) -> Self {
+        match kind {
+            TokenizerKind::Bert => ModelType::Bert,
+            TokenizerKind::DistilBert => ModelType::DistilBert,
+            TokenizerKind::Roberta => ModelType::Roberta,
+            TokenizerKind::Gpt2 => ModelType::GPT2,
+            TokenizerKind::Albert => ModelType::Albert,
+            TokenizerKind::XlmRoberta => ModelType::XLMRoberta,
+        }
+    }
+}
+
+/// A tokenizer that is loaded from vocab files without a model.
+pub struct StandaloneTokenizer {
+    tokenizer: TokenizerOption,
+    max_length: usize,
+}
+
+impl StandaloneTokenizer {
+    pub fn tokenizer(&self) -> &TokenizerOption {
+        &self.tokenizer
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+/// Counts the tokens in the vocab of the tokenizer, for the kinds of tokenizers that
+/// `TokenizerInit` and the BERT models load.
+pub fn vocab_size(tokenizer: &TokenizerOption) -> Option<usize> {
+    let vocab = match tokenizer {
+        TokenizerOption::Bert(t) => t.vocab().values(),
+        TokenizerOption::Roberta(t) => t.vocab().values(),
+        TokenizerOption::GPT2(t) => t.vocab().values(),
+        TokenizerOption::Albert(t) => t.vocab().values(),
+        TokenizerOption::XLMRoberta(t) => t.vocab().values(),
+        _ => return None,
+    };
+    Some(vocab.len())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenizerInit {
+    pub kind: TokenizerKind,
+    /// The vocab file (e.g. `vocab.txt` or `vocab.json`).
+    pub vocab_path: String,
+    /// The merges file of BPE tokenizers (e.g. `merges.txt`).
+    pub merges_path: Option<String>,
+    #[serde(default)]
+    pub lower_case: bool,
+    pub strip_accents: Option<bool>,
+    pub add_prefix_space: Option<bool>,
+    /// The maximum number of tokens of an input, defaults to 512.
+    pub max_length: Option<usize>,
+}
+
+impl TokenizerInit {
+    pub fn create(&self) -> Result<StandaloneTokenizer, anyhow::Error> {
+        let tokenizer = TokenizerOption::from_file(
+            self.kind.into(),
+            &self.vocab_path,
+            self.merges_path.as_deref(),
+            self.lower_case,
+            self.strip_accents,
+            self.add_prefix_space,
+        )
+        .context("Failed to load tokenizer.")?;
+
+        Ok(StandaloneTokenizer {
+            tokenizer,
+            max_length: self.max_length.unwrap_or(DEFAULT_MAX_LENGTH),
+        })
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_tokenizer(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: TokenizerInit =
+            codec::decode(bytes).context("Failed to parse tokenizer config.")?;
+
+        models::load(bytes, move || Ok(Model::Tokenizer(init.create()?))).map(|rid| rid as isize)
+    })
+}
+
+/// Calls `f` with the tokenizer and max sequence length of the model at the rid. Only the
+/// models that bertml loads itself expose their tokenizer: standalone tokenizers, question
+/// answering models and the models built from BERT checkpoints (fill-mask and encoder models).
+/// The rust-bert pipelines (e.g. NER or sentiment) are rejected.
+fn with_tokenizer<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+where
+    F: FnOnce(&TokenizerOption, usize) -> Result<T, anyhow::Error>,
+{
+    models::with_lookup(rid, |model| match model {
+        Model::Tokenizer(t) => f(&t.tokenizer, t.max_length),
+        Model::FillMaskModel(m) => f(m.tokenizer(), m.max_length()),
+        Model::EncoderModel(m) => f(m.tokenizer(), m.max_length()),
+        Model::QuestionAnsweringModel(m) => f(m.tokenizer(), m.max_length()),
+        _ => Err(anyhow::anyhow!(
+            "Model at rid '{}' is a {}, which does not expose its tokenizer. Only tokenizers, \
+             question answering, fill-mask and encoder models do, create a tokenizer from its \
+             vocab files instead.",
+            rid,
+            model.kind()
+        )),
+    })
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenizeInit {
+    pub inputs: Vec<String>,
+    /// Whether to add the special tokens of the model (e.g. `[CLS]` and `[SEP]`).
+    #[serde(default = "default_true")]
+    pub add_special_tokens: bool,
+    /// Truncates the inputs to this many tokens, defaults to the max sequence length.
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JSTokenized {
+    pub ids: Vec<i64>,
+    pub tokens: Vec<String>,
+    /// The UTF-16 offsets of each token in the input, `None` for special tokens.
+    pub offsets: Vec<Option<(usize, usize)>>,
+    pub special_tokens_mask: Vec<i8>,
+    /// The number of tokens that were cut off by truncation.
+    pub num_truncated: usize,
+}
+
+/// Tokenizes the input, truncating it to `max_length` tokens.
+pub fn tokenize(
+    tokenizer: &TokenizerOption,
+    input: &str,
+    add_special_tokens: bool,
+    max_length: usize,
+) -> JSTokenized {
+    let tokens = tokenizer.tokenize_with_offsets(input);
+    // The special tokens count towards the max length even when they are left out
+    let special_tokens = tokenizer
+        .build_input_with_special_tokens(
+            TokenIdsWithOffsets {
+                ids: Vec::new(),
+                offsets: Vec::new(),
+                reference_offsets: Vec::new(),
+                masks: Vec::new(),
+            },
+            None,
+        )
+        .token_ids
+        .len();
+    let len = tokens
+        .tokens
+        .len()
+        .min(max_length.saturating_sub(special_tokens));
+    let encoded = tokenizer.build_input_with_special_tokens(
+        TokenIdsWithOffsets {
+            ids: tokenizer.convert_tokens_to_ids(&tokens.tokens[..len]),
+            offsets: tokens.offsets[..len].to_vec(),
+            reference_offsets: tokens.reference_offsets[..len].to_vec(),
+            masks: tokens.masks[..len].to_vec(),
+        },
+        None,
+    );
+    let num_truncated = tokens.tokens.len() - len;
+    // The model-specific token strings, which `build_input_with_special_tokens` does not return
+    let mut tokens = tokens.tokens.into_iter().take(len);
+    // Offsets past the end of the input are clamped like `char_to_utf16_offset` does
+    let utf16_offsets = text::utf16_offsets(input);
+    let utf16 = |i: u32| utf16_offsets[(i as usize).min(utf16_offsets.len() - 1)];
+
+    let mut tokenized = JSTokenized {
+        ids: Vec::new(),
+        tokens: Vec::new(),
+        offsets: Vec::new(),
+        special_tokens_mask: Vec::new(),
+        num_truncated,
+    };
+    for ((&id, &special), offset) in encoded
+        .token_ids
+        .iter()
+        .zip(&encoded.special_tokens_mask)
+        .zip(&encoded.token_offsets)
+    {
+        let is_special = special == 1;
+        if is_special && !add_special_tokens {
+            continue;
+        }
+        tokenized.ids.push(id);
+        let token = if is_special { None } else { tokens.next() };
+        tokenized
+            .tokens
+            .push(token.unwrap_or_else(|| tokenizer.decode(&[id], false, false)));
+        tokenized
+            .offsets
+            .push(offset.map(|o| (utf16(o.begin), utf16(o.end))));
+        tokenized.special_tokens_mask.push(special as i8);
+    }
+    tokenized
+}
+
+/// Tokenizes each of the inputs, truncating them to `init.max_length` or else `max_length` tokens.
+pub fn tokenize_batch(
+    tokenizer: &TokenizerOption,
+    init: &TokenizeInit,
+    max_length: usize,
+) -> Vec<JSTokenized> {
+    let max_length = init.max_length.unwrap_or(max_length);
+    init.inputs
+        .iter()
+        .map(|input| tokenize(tokenizer, input, init.add_special_tokens, max_length))
+        .collect()
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn tokenizer_encode(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: TokenizeInit = codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            .context("Failed to parse tokenizer input.")?;
+
+        with_tokenizer(rid, |tokenizer, max_length| {
+            let outputs = codec::encode(&tokenize_batch(tokenizer, &init, max_length))
+                .context("Failed to serialize tokenizer output.")?;
+            Ok(set_result(outputs) as isize)
+        })
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeInit {
+    pub ids: Vec<Vec<i64>>,
+    #[serde(default = "default_true")]
+    pub skip_special_tokens: bool,
+    #[serde(default = "default_true")]
+    pub clean_up_tokenization_spaces: bool,
+}
+
+/// Decodes each of the token id sequences into text.
+pub fn decode_batch(tokenizer: &TokenizerOption, init: &DecodeInit) -> Vec<String> {
+    init.ids
+        .iter()
+        .map(|ids| {
+            tokenizer.decode(
+                ids,
+                init.skip_special_tokens,
+                init.clean_up_tokenization_spaces,
+            )
+        })
+        .collect()
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn tokenizer_decode(rid: usize, buf: *const u8, buf_len: usize) -> isize {
+    exec(|| {
+        let init: DecodeInit = codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            .context("Failed to parse tokenizer decode input.")?;
+
+        with_tokenizer(rid, |tokenizer, _| {
+            let outputs = codec::encode(&decode_batch(tokenizer, &init))
+                .context("Failed to serialize decoded text.")?;
+            Ok(set_result(outputs) as isize)
+        })
+    })
+}
+
+/// Returns the maximum number of tokens that the model accepts.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn tokenizer_max_length(rid: usize) -> isize {
+    exec(|| with_tokenizer(rid, |_, max_length| Ok(max_length as isize)))
+}
@@ -1,6 +1,6 @@
-use crate::{exec, models, set_result, Model};
+use crate::{codec, exec, ffi, models, set_result, Model};
 use anyhow::Context;
-use rust_bert::pipelines::translation::{self, TranslationModelBuilder};
+use rust_bert::pipelines::translation::{self, TranslationModel, TranslationModelBuilder};
 use serde::Deserialize;
 
 macro_rules! copy_enum {
@@ -130,58 +130,75 @@ copy_enum! {
     }
 }
 
+/// The languages of a translation model, as the indices of `Language`.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationModelInit {
-    source_languages: Vec<u8>,
-    target_languages: Vec<u8>,
+    pub source_languages: Vec<u8>,
+    pub target_languages: Vec<u8>,
 }
 
-#[no_mangle]
-extern "C" fn create_translation_model(init: *const u8, init_len: usize) -> isize {
-    exec(|| {
-        let init = unsafe { std::slice::from_raw_parts(init, init_len) };
-        let TranslationModelInit {
-            source_languages,
-            target_languages,
-        } = serde_json::from_slice(init).context("Failed to parse translation model config.")?;
-        let source_languages: Vec<translation::Language> = source_languages
-            .into_iter()
-            .map(|l| Language::from(Language::from_u8(l)))
+impl TranslationModelInit {
+    pub fn create(&self) -> Result<TranslationModel, anyhow::Error> {
+        let source_languages: Vec<translation::Language> = self
+            .source_languages
+            .iter()
+            .map(|&l| Language::from(Language::from_u8(l)))
             .collect::<Vec<_>>();
-        let target_languages: Vec<translation::Language> = target_languages
-            .into_iter()
-            .map(|l| Language::from(Language::from_u8(l)))
+        let target_languages: Vec<translation::Language> = self
+            .target_languages
+            .iter()
+            .map(|&l| Language::from(Language::from_u8(l)))
             .collect::<Vec<_>>();
-        let model = TranslationModelBuilder::new()
+        TranslationModelBuilder::new()
             .with_source_languages(source_languages)
             .with_target_languages(target_languages)
             .create_model()
-            .context("Failed to create model.")?;
-        models::allocate(Model::TranslationModel(model)).map(|a| a as isize)
+            .context("Failed to create model.")
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_translation_model(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let bytes = unsafe { std::slice::from_raw_parts(init, init_len) };
+        let init: TranslationModelInit =
+            codec::decode(bytes).context("Failed to parse translation model config.")?;
+        // The builder picks the weights itself, so the memory budget counts how much the resident
+        // memory grows while the model loads instead of the size of its weights
+        models::load(bytes, move || Ok(Model::TranslationModel(init.create()?))).map(|a| a as isize)
     })
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationInit {
-    inputs: Vec<String>,
-    source_language: u8,
-    target_language: u8,
+    pub inputs: Vec<String>,
+    pub source_language: u8,
+    pub target_language: u8,
 }
 
+/// Translates each of the inputs.
+pub fn translate(
+    model: &TranslationModel,
+    init: &TranslationInit,
+) -> Result<Vec<String>, anyhow::Error> {
+    let source_language = Language::from(Language::from_u8(init.source_language));
+    let target_language = Language::from(Language::from_u8(init.target_language));
+
+    model
+        .translate(&init.inputs, source_language, target_language)
+        .context("Failed to translate.")
+}
+
+#[ffi]
 #[no_mangle]
-extern "C" fn translation_translate(rid: usize, init: *const u8, init_len: usize) -> isize {
+pub extern "C" fn translation_translate(rid: usize, init: *const u8, init_len: usize) -> isize {
     exec(|| {
-        let TranslationInit {
-            inputs,
-            source_language,
-            target_language,
-        } = serde_json::from_slice(unsafe { std::slice::from_raw_parts(init, init_len) })
-            .context("Failed to parse translation model init.")?;
-
-        let source_language = Language::from(Language::from_u8(source_language));
-        let target_language = Language::from(Language::from_u8(target_language));
+        let init: TranslationInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(init, init_len) })
+                .context("Failed to parse translation model init.")?;
 
         let res = models::with_access(rid, |model| {
             let model = match model {
@@ -194,14 +211,12 @@ extern "C" fn translation_translate(rid: usize, init: *const u8, init_len: usize
                 }
             };
 
-            model
-                .translate(&inputs, source_language, target_language)
-                .context("Failed to translate.")
+            translate(model, &init)
         })
         .context("Failed to access translation model.")?;
 
         Ok(
-            set_result(serde_json::to_vec(&res).context("Failed to serialize translation data.")?)
+            set_result(codec::encode(&res).context("Failed to serialize translation data.")?)
                 as isize,
         )
     })
@@ -0,0 +1,1249 @@
+use crate::{
+    codec, encode_sentences, exec, ffi, model_resources, normalize, set_result, ModelResource,
+    ResultFormat, TensorFrame,
+};
+use anyhow::Context;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const FILE_MAGIC: &[u8; 8] = b"BMLVIDX\0";
+const FILE_VERSION: u32 = 1;
+/// The most layers that a node of a loaded HNSW graph can be on. `Hnsw::random_level` never goes
+/// above 54 since the uniform sample is at least 2^-54 and `m` is at least 2.
+const MAX_HNSW_LAYERS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Metric {
+    /// Vectors are normalized when they are added and searched so that the dot product is the
+    /// cosine similarity.
+    Cosine,
+    Dot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexKind {
+    /// Compares the query to every vector.
+    Exact,
+    /// Searches an approximate Hierarchical Navigable Small World graph.
+    Hnsw,
+}
+
+/// A node paired with its similarity to a query, ordered by the similarity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored(f32, u32);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or(Ordering::Equal)
+            .then(self.1.cmp(&other.1))
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// The vectors of a memory-mapped index file.
+struct MappedVectors {
+    mmap: Mmap,
+    /// The byte offset of the vectors in the file, which is a multiple of 4.
+    offset: usize,
+    count: usize,
+}
+
+/// Contiguous storage of vectors of the same dimension. The vectors of a memory-mapped file come
+/// before the vectors that were added after it was loaded.
+pub struct VectorStore {
+    dimension: usize,
+    mapped: Option<MappedVectors>,
+    data: Vec<f32>,
+}
+
+impl VectorStore {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            mapped: None,
+            data: Vec::new(),
+        }
+    }
+
+    fn mapped_count(&self) -> usize {
+        self.mapped.as_ref().map_or(0, |m| m.count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mapped_count() + self.data.len() / self.dimension.max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, slot: usize) -> &[f32] {
+        match &self.mapped {
+            Some(mapped) if slot < mapped.count => {
+                let start = mapped.offset + slot * self.dimension * 4;
+                let bytes = &mapped.mmap[start..start + self.dimension * 4];
+                // The file is little-endian (only mapped on little-endian targets) and the offset
+                // of the vectors is aligned to 4 bytes from the page-aligned start of the map.
+                unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const f32, self.dimension) }
+            }
+            _ => {
+                let slot = slot - self.mapped_count();
+                &self.data[slot * self.dimension..(slot + 1) * self.dimension]
+            }
+        }
+    }
+
+    pub fn push(&mut self, vector: &[f32]) {
+        self.data.extend_from_slice(vector);
+    }
+}
+
+/// A Hierarchical Navigable Small World graph over the slots of a `VectorStore`.
+pub struct Hnsw {
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    /// The neighbors of each node on each of the layers that it is on.
+    layers: Vec<Vec<Vec<u32>>>,
+    entry: Option<u32>,
+    rng: u64,
+}
+
+impl Hnsw {
+    pub fn new(m: usize, ef_construction: usize, ef_search: usize) -> Self {
+        Self {
+            m: m.max(2),
+            ef_construction,
+            ef_search,
+            layers: Vec::new(),
+            entry: None,
+            rng: 0x853c_49e6_748f_ea9b,
+        }
+    }
+
+    /// Picks the top layer of a new node from an exponentially decaying distribution.
+    fn random_level(&mut self) -> usize {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let x = self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d);
+        let uniform = ((x >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        (-uniform.ln() / (self.m as f64).ln()) as usize
+    }
+
+    fn max_neighbors(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    /// Finds the `ef` nodes on the layer that are the most similar to the query, starting from
+    /// the entry points. Nodes that are not included are still traversed.
+    fn search_layer(
+        &self,
+        vectors: &VectorStore,
+        query: &[f32],
+        entry_points: &[Scored],
+        ef: usize,
+        layer: usize,
+        include: &dyn Fn(u32) -> bool,
+    ) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entry_points.iter().map(|s| s.1).collect();
+        let mut candidates: BinaryHeap<Scored> = entry_points.iter().copied().collect();
+        let mut results: BinaryHeap<Reverse<Scored>> = entry_points
+            .iter()
+            .filter(|s| include(s.1))
+            .map(|&s| Reverse(s))
+            .collect();
+
+        while let Some(candidate) = candidates.pop() {
+            match results.peek() {
+                Some(Reverse(worst)) if results.len() >= ef && candidate.0 < worst.0 => break,
+                _ => {}
+            }
+
+            for &neighbor in &self.layers[candidate.1 as usize][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+
+                let score = dot(query, vectors.get(neighbor as usize));
+                let is_better = match results.peek() {
+                    Some(Reverse(worst)) => results.len() < ef || score > worst.0,
+                    None => true,
+                };
+                if is_better {
+                    candidates.push(Scored(score, neighbor));
+                    if include(neighbor) {
+                        results.push(Reverse(Scored(score, neighbor)));
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Scored> = results.into_iter().map(|r| r.0).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    /// Greedily moves from the entry point down to the given layer.
+    fn descend(&self, vectors: &VectorStore, query: &[f32], to_layer: usize) -> Vec<Scored> {
+        let entry = match self.entry {
+            Some(e) => e,
+            None => return Vec::new(),
+        };
+        let mut entry_points = vec![Scored(dot(query, vectors.get(entry as usize)), entry)];
+        for layer in (to_layer + 1..self.layers[entry as usize].len()).rev() {
+            entry_points = self.search_layer(vectors, query, &entry_points, 1, layer, &|_| true);
+        }
+        entry_points
+    }
+
+    /// Links the node at the slot into the graph. The vector must already be in the store.
+    pub fn insert(&mut self, slot: u32, vectors: &VectorStore) {
+        let level = self.random_level();
+        self.layers.push(vec![Vec::new(); level + 1]);
+
+        let top = match self.entry {
+            Some(entry) => self.layers[entry as usize].len() - 1,
+            None => {
+                self.entry = Some(slot);
+                return;
+            }
+        };
+
+        let query = vectors.get(slot as usize);
+        let mut entry_points = self.descend(vectors, query, level.min(top));
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(
+                vectors,
+                query,
+                &entry_points,
+                self.ef_construction,
+                layer,
+                &|_| true,
+            );
+            let max_neighbors = self.max_neighbors(layer);
+            let neighbors: Vec<u32> = found.iter().take(self.m).map(|s| s.1).collect();
+
+            for &neighbor in &neighbors {
+                let links = &mut self.layers[neighbor as usize][layer];
+                links.push(slot);
+                if links.len() > max_neighbors {
+                    let base = vectors.get(neighbor as usize);
+                    let mut scored: Vec<Scored> = links
+                        .iter()
+                        .map(|&n| Scored(dot(base, vectors.get(n as usize)), n))
+                        .collect();
+                    scored.sort_by(|a, b| b.cmp(a));
+                    *links = scored
+                        .into_iter()
+                        .take(max_neighbors)
+                        .map(|s| s.1)
+                        .collect();
+                }
+            }
+
+            self.layers[slot as usize][layer] = neighbors;
+            entry_points = found;
+        }
+
+        if level > top {
+            self.entry = Some(slot);
+        }
+    }
+
+    /// Checks that the entry point and every neighbor are nodes that are on the layer that they
+    /// are linked on, so that a corrupt file cannot make a search index out of bounds.
+    fn validate(&self) -> Result<(), anyhow::Error> {
+        if let Some(entry) = self.entry {
+            if entry as usize >= self.layers.len() {
+                return Err(anyhow::anyhow!(
+                    "Invalid HNSW entry point {} in vector index file.",
+                    entry
+                ));
+            }
+        }
+        for (slot, layers) in self.layers.iter().enumerate() {
+            for (layer, neighbors) in layers.iter().enumerate() {
+                for &neighbor in neighbors {
+                    if self
+                        .layers
+                        .get(neighbor as usize)
+                        .map_or(true, |layers| layers.len() <= layer)
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Invalid HNSW neighbor {} of slot {} in vector index file.",
+                            neighbor,
+                            slot
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn search(
+        &self,
+        vectors: &VectorStore,
+        query: &[f32],
+        k: usize,
+        include: &dyn Fn(u32) -> bool,
+    ) -> Vec<Scored> {
+        let entry_points = self.descend(vectors, query, 0);
+        if entry_points.is_empty() {
+            return entry_points;
+        }
+        let mut found = self.search_layer(
+            vectors,
+            query,
+            &entry_points,
+            self.ef_search.max(k),
+            0,
+            include,
+        );
+        found.truncate(k);
+        found
+    }
+}
+
+/// Vectors with string ids that can be searched by similarity. Removed vectors are only marked
+/// as deleted so that the slots of the other vectors (and the HNSW graph) stay valid. The slots of
+/// removed and replaced vectors are never reclaimed, not even by `save`, so an index that has many
+/// of its vectors replaced keeps growing and is better rebuilt from the vectors that remain.
+pub struct VectorIndex {
+    /// The id of the model that the vectors were created with.
+    model_id: String,
+    metric: Metric,
+    ids: Vec<String>,
+    slots: HashMap<String, u32>,
+    deleted: Vec<bool>,
+    vectors: VectorStore,
+    hnsw: Option<Hnsw>,
+}
+
+impl VectorIndex {
+    pub fn new(dimension: usize, metric: Metric, model_id: String, hnsw: Option<Hnsw>) -> Self {
+        Self {
+            model_id,
+            metric,
+            ids: Vec::new(),
+            slots: HashMap::new(),
+            deleted: Vec::new(),
+            vectors: VectorStore::new(dimension),
+            hnsw,
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.vectors.dimension
+    }
+
+    /// The number of vectors that have not been removed.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Adds the vector, replacing the vector that already has the id.
+    pub fn add(&mut self, id: String, mut vector: Vec<f32>) -> Result<(), anyhow::Error> {
+        if vector.len() != self.dimension() {
+            return Err(anyhow::anyhow!(
+                "Expected vector with a dimension of {} but got {}.",
+                self.dimension(),
+                vector.len()
+            ));
+        }
+        if self.metric == Metric::Cosine {
+            normalize(&mut vector);
+        }
+
+        if let Some(old) = self.slots.get(&id) {
+            self.deleted[*old as usize] = true;
+        }
+        let slot = self.ids.len() as u32;
+        self.ids.push(id.clone());
+        self.slots.insert(id, slot);
+        self.deleted.push(false);
+        self.vectors.push(&vector);
+        if let Some(hnsw) = &mut self.hnsw {
+            hnsw.insert(slot, &self.vectors);
+        }
+        Ok(())
+    }
+
+    /// Removes the vector with the id and returns whether there was one.
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.slots.remove(id) {
+            Some(slot) => {
+                self.deleted[slot as usize] = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the ids and similarities of the `k` vectors that are the most similar to the query.
+    pub fn search(&self, mut query: Vec<f32>, k: usize) -> Result<Vec<(&str, f32)>, anyhow::Error> {
+        if query.len() != self.dimension() {
+            return Err(anyhow::anyhow!(
+                "Expected query with a dimension of {} but got {}.",
+                self.dimension(),
+                query.len()
+            ));
+        }
+        if self.metric == Metric::Cosine {
+            normalize(&mut query);
+        }
+
+        let found = match &self.hnsw {
+            Some(hnsw) => hnsw.search(&self.vectors, &query, k, &|n| !self.deleted[n as usize]),
+            None => {
+                let mut best: BinaryHeap<Reverse<Scored>> = BinaryHeap::with_capacity(k + 1);
+                for slot in (0..self.ids.len()).filter(|&s| !self.deleted[s]) {
+                    best.push(Reverse(Scored(
+                        dot(&query, self.vectors.get(slot)),
+                        slot as u32,
+                    )));
+                    if best.len() > k {
+                        best.pop();
+                    }
+                }
+                let mut found: Vec<Scored> = best.into_iter().map(|r| r.0).collect();
+                found.sort_by(|a, b| b.cmp(a));
+                found
+            }
+        };
+
+        Ok(found
+            .into_iter()
+            .map(|s| (self.ids[s.1 as usize].as_str(), s.0))
+            .collect())
+    }
+}
+
+/// Writes little-endian values and keeps track of the position for alignment.
+struct FileWriter<W: Write> {
+    inner: W,
+    position: usize,
+}
+
+impl<W: Write> FileWriter<W> {
+    fn bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.inner.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    fn u32(&mut self, n: u32) -> std::io::Result<()> {
+        self.bytes(&n.to_le_bytes())
+    }
+
+    fn u64(&mut self, n: u64) -> std::io::Result<()> {
+        self.bytes(&n.to_le_bytes())
+    }
+
+    fn string(&mut self, s: &str) -> std::io::Result<()> {
+        self.u32(s.len() as u32)?;
+        self.bytes(s.as_bytes())
+    }
+
+    fn align(&mut self, to: usize) -> std::io::Result<()> {
+        let padding = (to - self.position % to) % to;
+        self.bytes(&[0; 8][..padding])
+    }
+}
+
+/// Reads little-endian values from the bytes of a file.
+struct FileReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> FileReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], anyhow::Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .context("Unexpected end of vector index file.")?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, anyhow::Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, anyhow::Error> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, anyhow::Error> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, anyhow::Error> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).context("Invalid string in vector index file.")
+    }
+
+    fn align(&mut self, to: usize) {
+        self.position += (to - self.position % to) % to;
+    }
+}
+
+/// The layout of an index file, where all numbers are little-endian:
+///
+/// - header: the magic bytes `BMLVIDX\0`, the version (`u32`), the dimension (`u32`), the metric
+///   (`u8`, 0 = cosine, 1 = dot), the kind (`u8`, 0 = exact, 1 = HNSW), 2 reserved bytes, the
+///   number of slots (`u64`) and the model id (`u32` length + UTF-8)
+/// - vectors: padding to a multiple of 4 bytes and then `slots * dimension` `f32`s
+/// - id table: the id of each slot (`u32` length + UTF-8)
+/// - deleted table: one `u8` for each slot, 1 if the vector was removed
+/// - HNSW graph (HNSW indexes only): `m`, `ef_construction`, `ef_search` and the entry point (all
+///   `u32`, `u32::MAX` for no entry point), the random state (`u64`) and for each slot the number
+///   of layers (`u32`) followed by the neighbors of each layer (`u32` count + `u32` slots)
+impl VectorIndex {
+    /// Writes the index to the file at the path. The index is written to a temporary file next to
+    /// it that then replaces it, since a memory-mapped index may be reading its vectors from the
+    /// file at the path.
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+        let temp = PathBuf::from(temp);
+
+        let result = self
+            .write(&temp)
+            .and_then(|_| fs::rename(&temp, path).context("Failed to replace vector index file."));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        result
+    }
+
+    fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let file = File::create(path).context("Failed to create vector index file.")?;
+        let mut w = FileWriter {
+            inner: BufWriter::new(file),
+            position: 0,
+        };
+
+        w.bytes(FILE_MAGIC)?;
+        w.u32(FILE_VERSION)?;
+        w.u32(self.dimension() as u32)?;
+        w.bytes(&[
+            match self.metric {
+                Metric::Cosine => 0,
+                Metric::Dot => 1,
+            },
+            self.hnsw.is_some() as u8,
+            0,
+            0,
+        ])?;
+        w.u64(self.ids.len() as u64)?;
+        w.string(&self.model_id)?;
+
+        w.align(4)?;
+        for slot in 0..self.vectors.len() {
+            for x in self.vectors.get(slot) {
+                w.bytes(&x.to_le_bytes())?;
+            }
+        }
+
+        for id in &self.ids {
+            w.string(id)?;
+        }
+        let deleted = self.deleted.iter().map(|&d| d as u8).collect::<Vec<_>>();
+        w.bytes(&deleted)?;
+
+        if let Some(hnsw) = &self.hnsw {
+            w.u32(hnsw.m as u32)?;
+            w.u32(hnsw.ef_construction as u32)?;
+            w.u32(hnsw.ef_search as u32)?;
+            w.u32(hnsw.entry.unwrap_or(u32::MAX))?;
+            w.u64(hnsw.rng)?;
+            for layers in &hnsw.layers {
+                w.u32(layers.len() as u32)?;
+                for neighbors in layers {
+                    w.u32(neighbors.len() as u32)?;
+                    for &neighbor in neighbors {
+                        w.u32(neighbor)?;
+                    }
+                }
+            }
+        }
+
+        w.inner
+            .flush()
+            .context("Failed to write vector index file.")?;
+        Ok(())
+    }
+
+    /// Reads the index from the file at the path. When `mmap` is true, the vectors are read from
+    /// a memory map of the file instead of being copied into memory.
+    pub fn load(path: &Path, mmap: bool) -> Result<VectorIndex, anyhow::Error> {
+        let file = File::open(path).context("Failed to open vector index file.")?;
+        let map = unsafe { Mmap::map(&file) }.context("Failed to map vector index file.")?;
+        let mut r = FileReader {
+            bytes: &map,
+            position: 0,
+        };
+
+        if r.bytes(FILE_MAGIC.len())? != FILE_MAGIC {
+            return Err(anyhow::anyhow!("Expected a bertml vector index file."));
+        }
+        let version = r.u32()?;
+        if version != FILE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported vector index file version {}.",
+                version
+            ));
+        }
+        let dimension = r.u32()? as usize;
+        if dimension == 0 {
+            return Err(anyhow::anyhow!(
+                "Vector index file has a vector dimension of 0."
+            ));
+        }
+        let metric = match r.u8()? {
+            0 => Metric::Cosine,
+            1 => Metric::Dot,
+            n => return Err(anyhow::anyhow!("Unknown vector index metric {}.", n)),
+        };
+        let has_hnsw = r.u8()? == 1;
+        r.bytes(2)?;
+        let count = usize::try_from(r.u64()?).context("Too many vectors in vector index file.")?;
+        let model_id = r.string()?;
+
+        r.align(4);
+        let vectors_offset = r.position;
+        let vectors_len = count
+            .checked_mul(dimension)
+            .and_then(|n| n.checked_mul(4))
+            .context("Too many vectors in vector index file.")?;
+        let vector_bytes = r.bytes(vectors_len)?;
+        let data = if mmap && cfg!(target_endian = "little") {
+            Vec::new()
+        } else {
+            vector_bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect()
+        };
+
+        let ids = (0..count)
+            .map(|_| r.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let deleted = r.bytes(count)?.iter().map(|&d| d != 0).collect::<Vec<_>>();
+
+        let hnsw = if has_hnsw {
+            let mut hnsw = Hnsw::new(r.u32()? as usize, r.u32()? as usize, r.u32()? as usize);
+            hnsw.entry = Some(r.u32()?).filter(|&e| e != u32::MAX);
+            hnsw.rng = r.u64()?;
+            for _ in 0..count {
+                let layer_count = r.u32()? as usize;
+                if !(1..=MAX_HNSW_LAYERS).contains(&layer_count) {
+                    return Err(anyhow::anyhow!(
+                        "Invalid number of HNSW layers {} in vector index file.",
+                        layer_count
+                    ));
+                }
+                let layers = (0..layer_count)
+                    .map(|_| {
+                        let len = r.u32()?;
+                        (0..len).map(|_| r.u32()).collect::<Result<Vec<_>, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                hnsw.layers.push(layers);
+            }
+            hnsw.validate()?;
+            Some(hnsw)
+        } else {
+            None
+        };
+
+        let slots = ids
+            .iter()
+            .enumerate()
+            .filter(|(slot, _)| !deleted[*slot])
+            .map(|(slot, id)| (id.clone(), slot as u32))
+            .collect();
+        let vectors = VectorStore {
+            dimension,
+            mapped: if data.is_empty() && count * dimension > 0 {
+                Some(MappedVectors {
+                    mmap: map,
+                    offset: vectors_offset,
+                    count,
+                })
+            } else {
+                None
+            },
+            data,
+        };
+
+        Ok(VectorIndex {
+            model_id,
+            metric,
+            ids,
+            slots,
+            deleted,
+            vectors,
+            hnsw,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorIndexInit {
+    pub dimension: usize,
+    /// The id of the model that the vectors are created with, which is saved with the index.
+    pub model_id: Option<String>,
+    pub metric: Option<Metric>,
+    pub kind: Option<IndexKind>,
+    /// The number of neighbors of each HNSW node.
+    pub m: Option<usize>,
+    pub ef_construction: Option<usize>,
+    pub ef_search: Option<usize>,
+}
+
+impl VectorIndexInit {
+    pub fn create(&self) -> Result<VectorIndex, anyhow::Error> {
+        if self.dimension == 0 {
+            return Err(anyhow::anyhow!("Expected a vector dimension above 0."));
+        }
+        let hnsw = match self.kind.unwrap_or(IndexKind::Exact) {
+            IndexKind::Exact => None,
+            IndexKind::Hnsw => {
+                let m = self.m.unwrap_or(16);
+                let ef_construction = self.ef_construction.unwrap_or(200);
+                if m == 0 || ef_construction == 0 {
+                    return Err(anyhow::anyhow!(
+                        "Expected HNSW m and efConstruction above 0 but got {} and {}.",
+                        m,
+                        ef_construction
+                    ));
+                }
+                Some(Hnsw::new(m, ef_construction, self.ef_search.unwrap_or(64)))
+            }
+        };
+        Ok(VectorIndex::new(
+            self.dimension,
+            self.metric.unwrap_or(Metric::Cosine),
+            self.model_id.clone().unwrap_or_default(),
+            hnsw,
+        ))
+    }
+}
+
+#[derive(Serialize)]
+pub struct JSSearchResult<'a> {
+    pub id: &'a str,
+    pub score: f32,
+}
+
+/// Reads little-endian `f32`s from the bytes.
+fn read_f32s(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+/// Runs `f` with the vector index at the resource id.
+fn with_index<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+where
+    F: FnOnce(&mut VectorIndex) -> Result<T, anyhow::Error>,
+{
+    model_resources::with_access(rid, |resource| match resource {
+        ModelResource::VectorIndex(index) => f(index),
+        _ => Err(anyhow::anyhow!(
+            "Expected vector index at resource id '{}'.",
+            rid
+        )),
+    })
+}
+
+impl VectorIndex {
+    /// Adds the vectors with the ids and returns the number of vectors in the index.
+    pub fn add_all(
+        &mut self,
+        ids: Vec<String>,
+        vectors: Vec<Vec<f32>>,
+    ) -> Result<usize, anyhow::Error> {
+        if ids.len() != vectors.len() {
+            return Err(anyhow::anyhow!(
+                "Expected {} vectors for the ids but got {}.",
+                ids.len(),
+                vectors.len()
+            ));
+        }
+        // Checked up front so that nothing is added when any of the vectors is invalid
+        if let Some(vector) = vectors.iter().find(|v| v.len() != self.dimension()) {
+            return Err(anyhow::anyhow!(
+                "Expected vector with a dimension of {} but got {}.",
+                self.dimension(),
+                vector.len()
+            ));
+        }
+        for (id, vector) in ids.into_iter().zip(vectors) {
+            self.add(id, vector)?;
+        }
+        Ok(self.len())
+    }
+
+    /// Searches for the `k` most similar vectors to each of the queries.
+    pub fn search_all(
+        &self,
+        queries: Vec<Vec<f32>>,
+        k: usize,
+    ) -> Result<Vec<Vec<JSSearchResult<'_>>>, anyhow::Error> {
+        queries
+            .into_iter()
+            .map(|query| {
+                Ok(self
+                    .search(query, k)?
+                    .into_iter()
+                    .map(|(id, score)| JSSearchResult { id, score })
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+fn search_with_format(
+    index: &VectorIndex,
+    queries: Vec<Vec<f32>>,
+    k: usize,
+    format: ResultFormat,
+) -> Result<isize, anyhow::Error> {
+    let results = index.search_all(queries, k)?;
+
+    let bytes = match format {
+        ResultFormat::Tensor => {
+            // Queries with fewer than `k` results are padded with NaN scores
+            let mut scores = vec![f32::NAN; results.len() * k];
+            for (i, results) in results.iter().enumerate() {
+                for (j, result) in results.iter().enumerate() {
+                    scores[i * k + j] = result.score;
+                }
+            }
+
+            let mut frame = TensorFrame::new();
+            frame.push_f32("scores", vec![results.len() as i64, k as i64], &scores);
+            frame.into_bytes(SearchTensorMetadata {
+                ids: results
+                    .iter()
+                    .map(|results| results.iter().map(|r| r.id).collect())
+                    .collect(),
+            })?
+        }
+        ResultFormat::Json => {
+            codec::encode(&results).context("Failed to serialize vector index results.")?
+        }
+    };
+    Ok(set_result(bytes) as isize)
+}
+
+#[derive(Serialize)]
+struct SearchTensorMetadata<'a> {
+    /// The ids of the results of each query, in the order of the scores.
+    ids: Vec<Vec<&'a str>>,
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn create_vector_index(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let init: VectorIndexInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(init, init_len) })
+                .context("Failed to parse vector index config.")?;
+
+        model_resources::allocate(ModelResource::VectorIndex(init.create()?))
+            .context("Failed to allocate vector index.")
+            .map(|a| a as isize)
+    })
+}
+
+/// Adds the vectors (contiguous little-endian `f32`s) with the ids (an array in the codec of the
+/// library) and returns the number of vectors in the index.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_add(
+    rid: usize,
+    ids: *const u8,
+    ids_len: usize,
+    vectors: *const u8,
+    vectors_len: usize,
+) -> isize {
+    exec(|| {
+        let ids: Vec<String> = codec::decode(unsafe { std::slice::from_raw_parts(ids, ids_len) })
+            .context("Failed to parse vector index ids.")?;
+        let vectors = unsafe { std::slice::from_raw_parts(vectors, vectors_len) };
+
+        with_index(rid, |index| {
+            if vectors.len() != ids.len() * index.dimension() * 4 {
+                return Err(anyhow::anyhow!(
+                    "Expected {} bytes for {} vectors with a dimension of {} but got {}.",
+                    ids.len() * index.dimension() * 4,
+                    ids.len(),
+                    index.dimension(),
+                    vectors.len()
+                ));
+            }
+            let vectors = read_f32s(vectors)
+                .chunks(index.dimension())
+                .map(|v| v.to_vec())
+                .collect();
+            index.add_all(ids, vectors).map(|len| len as isize)
+        })
+    })
+}
+
+#[derive(Deserialize)]
+pub struct VectorIndexAddTextsInit {
+    pub ids: Vec<String>,
+    pub texts: Vec<String>,
+}
+
+/// Encodes the texts with the sentence embeddings model and adds them with the ids.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_add_texts(
+    rid: usize,
+    model_rid: usize,
+    buf: *const u8,
+    buf_len: usize,
+) -> isize {
+    exec(|| {
+        let init: VectorIndexAddTextsInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse vector index texts.")?;
+
+        let vectors = encode_sentences(model_rid, &init.texts, false)?;
+        with_index(rid, |index| {
+            index.add_all(init.ids, vectors).map(|len| len as isize)
+        })
+    })
+}
+
+/// Removes the vectors with the ids (an array in the codec of the library) and returns the number
+/// that were removed.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_remove(rid: usize, ids: *const u8, ids_len: usize) -> isize {
+    exec(|| {
+        let ids: Vec<String> = codec::decode(unsafe { std::slice::from_raw_parts(ids, ids_len) })
+            .context("Failed to parse vector index ids.")?;
+
+        with_index(rid, |index| {
+            Ok(ids.iter().filter(|id| index.remove(id)).count() as isize)
+        })
+    })
+}
+
+/// Searches for the `k` most similar vectors to each of the queries (contiguous little-endian
+/// `f32`s).
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_search(
+    rid: usize,
+    queries: *const u8,
+    queries_len: usize,
+    k: usize,
+) -> isize {
+    exec(|| {
+        let queries = read_f32s(unsafe { std::slice::from_raw_parts(queries, queries_len) });
+
+        with_index(rid, |index| {
+            let queries = queries
+                .chunks(index.dimension().max(1))
+                .map(|q| q.to_vec())
+                .collect();
+            search_with_format(index, queries, k, ResultFormat::Json)
+        })
+    })
+}
+
+#[derive(Deserialize)]
+pub struct VectorIndexSearchTextsInit {
+    pub queries: Vec<String>,
+    pub k: usize,
+    /// With `tensor`, the result is a `scores` tensor of the shape `[queries, k]` padded with NaN
+    /// and the ids of the results in the metadata.
+    #[serde(default)]
+    pub format: ResultFormat,
+}
+
+/// Encodes the queries with the sentence embeddings model and searches for the `k` most similar
+/// vectors to each of them.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_search_texts(
+    rid: usize,
+    model_rid: usize,
+    buf: *const u8,
+    buf_len: usize,
+) -> isize {
+    exec(|| {
+        let init: VectorIndexSearchTextsInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to parse vector index text queries.")?;
+
+        let queries = encode_sentences(model_rid, &init.queries, false)?;
+        with_index(rid, |index| {
+            search_with_format(index, queries, init.k, init.format)
+        })
+    })
+}
+
+/// Saves the index to the file at the path (UTF-8 bytes).
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_save(rid: usize, path: *const u8, path_len: usize) -> isize {
+    exec(|| {
+        let path = std::str::from_utf8(unsafe { std::slice::from_raw_parts(path, path_len) })
+            .context("Expected vector index path to be UTF-8.")?;
+
+        with_index(rid, |index| index.save(Path::new(path)).map(|_| 0))
+    })
+}
+
+#[derive(Deserialize)]
+pub struct VectorIndexLoadInit {
+    pub path: String,
+    #[serde(default)]
+    pub mmap: bool,
+}
+
+/// Loads an index saved with `vector_index_save` and returns its resource id.
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_load(init: *const u8, init_len: usize) -> isize {
+    exec(|| {
+        let init: VectorIndexLoadInit =
+            codec::decode(unsafe { std::slice::from_raw_parts(init, init_len) })
+                .context("Failed to parse vector index load config.")?;
+
+        let index = VectorIndex::load(Path::new(&init.path), init.mmap)?;
+        model_resources::allocate(ModelResource::VectorIndex(index))
+            .context("Failed to allocate vector index.")
+            .map(|a| a as isize)
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JSVectorIndexInfo<'a> {
+    pub dimension: usize,
+    pub metric: Metric,
+    pub kind: IndexKind,
+    pub model_id: &'a str,
+    pub len: usize,
+}
+
+impl VectorIndex {
+    pub fn info(&self) -> JSVectorIndexInfo<'_> {
+        JSVectorIndexInfo {
+            dimension: self.dimension(),
+            metric: self.metric,
+            kind: match self.hnsw {
+                Some(_) => IndexKind::Hnsw,
+                None => IndexKind::Exact,
+            },
+            model_id: &self.model_id,
+            len: self.len(),
+        }
+    }
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn vector_index_info(rid: usize) -> isize {
+    exec(|| {
+        with_index(rid, |index| {
+            Ok(set_result(
+                codec::encode(&index.info()).context("Failed to serialize vector index info.")?,
+            ) as isize)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init(kind: IndexKind) -> VectorIndexInit {
+        VectorIndexInit {
+            dimension: 8,
+            model_id: Some("test".to_string()),
+            metric: None,
+            kind: Some(kind),
+            m: None,
+            ef_construction: None,
+            ef_search: None,
+        }
+    }
+
+    /// Deterministic pseudo-random vectors.
+    fn vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                (0..dimension)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        (state >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn filled(kind: IndexKind, count: usize) -> VectorIndex {
+        let mut index = init(kind).create().unwrap();
+        let ids = (0..count).map(|i| i.to_string()).collect();
+        index.add_all(ids, vectors(count, 8, 1)).unwrap();
+        index
+    }
+
+    fn ids(index: &VectorIndex, query: &[f32], k: usize) -> Vec<String> {
+        index
+            .search(query.to_vec(), k)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn hnsw_recalls_exact_neighbors() {
+        let exact = filled(IndexKind::Exact, 500);
+        let hnsw = filled(IndexKind::Hnsw, 500);
+
+        let mut found = 0;
+        let queries = vectors(20, 8, 2);
+        for query in &queries {
+            let expected = ids(&exact, query, 10);
+            found += ids(&hnsw, query, 10)
+                .iter()
+                .filter(|id| expected.contains(id))
+                .count();
+        }
+        assert!(found as f64 / (queries.len() * 10) as f64 >= 0.9);
+    }
+
+    #[test]
+    fn exact_search_finds_the_vector_itself() {
+        let index = filled(IndexKind::Exact, 50);
+        let query = &vectors(50, 8, 1)[7];
+        let results = index.search(query.clone(), 1).unwrap();
+        assert_eq!(results[0].0, "7");
+        assert!((results[0].1 - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn removed_and_replaced_vectors_are_not_found() {
+        let mut index = filled(IndexKind::Hnsw, 50);
+        let query = vectors(50, 8, 1)[3].clone();
+        assert!(index.remove("3"));
+        assert!(!index.remove("3"));
+        assert!(!ids(&index, &query, 50).contains(&"3".to_string()));
+
+        let replaced = vectors(1, 8, 3).remove(0);
+        index.add("4".to_string(), replaced.clone()).unwrap();
+        assert_eq!(index.len(), 49);
+        assert_eq!(ids(&index, &replaced, 1), vec!["4".to_string()]);
+    }
+
+    #[test]
+    fn add_all_rejects_invalid_vectors_without_adding_any() {
+        let mut index = init(IndexKind::Exact).create().unwrap();
+        let mut vectors = vectors(3, 8, 1);
+        vectors[2].pop();
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert!(index.add_all(ids, vectors).is_err());
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn create_rejects_empty_parameters() {
+        let mut hnsw = init(IndexKind::Hnsw);
+        hnsw.m = Some(0);
+        assert!(hnsw.create().is_err());
+
+        let mut hnsw = init(IndexKind::Hnsw);
+        hnsw.ef_construction = Some(0);
+        assert!(hnsw.create().is_err());
+
+        let mut exact = init(IndexKind::Exact);
+        exact.dimension = 0;
+        assert!(exact.create().is_err());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        for kind in [IndexKind::Exact, IndexKind::Hnsw] {
+            let mut index = filled(kind, 100);
+            index.remove("10");
+            let path = std::env::temp_dir().join(format!("bertml-{}.index", uuid::Uuid::new_v4()));
+            index.save(&path).unwrap();
+
+            for mmap in [false, true] {
+                let loaded = VectorIndex::load(&path, mmap).unwrap();
+                assert_eq!(loaded.len(), index.len());
+                assert_eq!(loaded.dimension(), index.dimension());
+                for query in &vectors(5, 8, 4) {
+                    assert_eq!(ids(&loaded, query, 10), ids(&index, query, 10));
+                }
+            }
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn load_rejects_truncated_files() {
+        let index = filled(IndexKind::Hnsw, 20);
+        let path = std::env::temp_dir().join(format!("bertml-{}.index", uuid::Uuid::new_v4()));
+        index.save(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        assert!(VectorIndex::load(&path, false).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,55 +1,143 @@
-use crate::{exec, models, set_result, Model};
+use crate::{
+    codec, exec, ffi, models, record_vocab, record_weights, set_result, Model, ResultFormat,
+    TensorFrame,
+};
 use anyhow::Context;
-use rust_bert::pipelines::zero_shot_classification::ZeroShotClassificationModel;
-use serde::Deserialize;
+use rust_bert::pipelines::sequence_classification::Label;
+use rust_bert::pipelines::zero_shot_classification::{
+    ZeroShotClassificationConfig, ZeroShotClassificationModel,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+/// The template rust-bert uses when none is given.
+const DEFAULT_TEMPLATE: &str = "This example is {}.";
+
+#[ffi]
 #[no_mangle]
 pub extern "C" fn create_zero_shot_model() -> isize {
     exec(|| {
-        let model = ZeroShotClassificationModel::new(Default::default())
-            .context("Failed to create zero shot classification model.")?;
-
-        models::allocate(Model::ZeroShotClassificationModel(model)).map(|a| a as isize)
+        models::load(&[], || {
+            let config = ZeroShotClassificationConfig::default();
+            record_weights(&*config.model_resource)?;
+            record_vocab(
+                config.model_type,
+                &*config.vocab_resource,
+                config.merges_resource.as_deref(),
+            )?;
+            let model = ZeroShotClassificationModel::new(config)
+                .context("Failed to create zero shot classification model.")?;
+            Ok(Model::ZeroShotClassificationModel(model))
+        })
+        .map(|a| a as isize)
     })
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ZeroShotInput {
-    inputs: Vec<String>,
-    labels: Vec<String>,
-    max_length: usize,
+    pub inputs: Vec<String>,
+    pub labels: Vec<String>,
+    pub max_length: usize,
+    /// The hypothesis for each label where `{}` is replaced by the label.
+    pub template: Option<String>,
+    /// Templates for specific labels that take priority over `template`.
+    #[serde(default)]
+    pub label_templates: HashMap<String, String>,
+    /// Text that is put into the hypothesis instead of the label itself.
+    #[serde(default)]
+    pub label_descriptions: HashMap<String, String>,
+}
+
+impl ZeroShotInput {
+    /// Creates the closure that rust-bert uses to build the hypothesis for each label.
+    fn hypothesis_template(&self) -> Result<Option<Box<dyn Fn(&str) -> String>>, anyhow::Error> {
+        if self.template.is_none()
+            && self.label_templates.is_empty()
+            && self.label_descriptions.is_empty()
+        {
+            return Ok(None);
+        }
+
+        for template in self.template.iter().chain(self.label_templates.values()) {
+            if !template.contains("{}") {
+                return Err(anyhow::anyhow!(
+                    "Expected hypothesis template '{}' to contain '{{}}'.",
+                    template
+                ));
+            }
+        }
+
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
+        let label_templates = self.label_templates.clone();
+        let label_descriptions = self.label_descriptions.clone();
+
+        Ok(Some(Box::new(move |label: &str| {
+            let template = label_templates.get(label).unwrap_or(&template);
+            let description = label_descriptions
+                .get(label)
+                .map(String::as_str)
+                .unwrap_or(label);
+            template.replace("{}", description)
+        })))
+    }
+}
+
+/// Predicts the most likely label of each input.
+pub fn classify(
+    model: &ZeroShotClassificationModel,
+    input: &ZeroShotInput,
+) -> Result<Vec<Label>, anyhow::Error> {
+    let inputs = &input.inputs.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    let labels = &input.labels.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    let template = input.hypothesis_template()?;
+    Ok(model.predict(inputs, labels, template, input.max_length))
+}
+
+/// Predicts the entailment score of every label for each input, in the order of the labels.
+pub fn classify_multilabel(
+    model: &ZeroShotClassificationModel,
+    input: &ZeroShotInput,
+) -> Result<Vec<Vec<Label>>, anyhow::Error> {
+    let inputs = &input.inputs.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    let labels = &input.labels.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+    let template = input.hypothesis_template()?;
+    Ok(model.predict_multilabel(inputs, labels, template, input.max_length))
+}
+
+fn with_zero_shot_model<T, F>(rid: usize, f: F) -> Result<T, anyhow::Error>
+where
+    F: FnOnce(&ZeroShotClassificationModel) -> Result<T, anyhow::Error>,
+{
+    models::with_access(rid, |model| match model {
+        Model::ZeroShotClassificationModel(m) => f(m),
+        _ => Err(anyhow::anyhow!(
+            "Expected zero shot classification model at rid '{}'.",
+            rid
+        )),
+    })
 }
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn zero_shot_predict(rid: usize, buf: *const u8, buf_len: usize) -> isize {
     exec(|| {
         let buf = unsafe { std::slice::from_raw_parts(buf, buf_len) };
         let input: ZeroShotInput =
-            serde_json::from_slice(buf).context("Failed to deserialize zero shot model config.")?;
-
-        let labels = models::with_access(rid, |model| {
-            let model = match model {
-                Model::ZeroShotClassificationModel(m) => m,
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "Expected zero shot classification model at rid '{}'.",
-                        rid
-                    ))
-                }
-            };
-            let inputs = &input.inputs.iter().map(|a| a.as_str()).collect::<Vec<_>>();
-            let labels = &input.labels.iter().map(|a| a.as_str()).collect::<Vec<_>>();
-            Ok(model.predict(inputs, labels, None, input.max_length))
-        })?;
+            codec::decode(buf).context("Failed to deserialize zero shot model config.")?;
+
+        let labels = with_zero_shot_model(rid, |model| classify(model, &input))?;
         Ok(
-            set_result(
-                serde_json::to_vec(&labels).context("Failed to serialize zero shot output.")?,
-            ) as isize,
+            set_result(codec::encode(&labels).context("Failed to serialize zero shot output.")?)
+                as isize,
         )
     })
 }
 
+#[ffi]
 #[no_mangle]
 pub extern "C" fn zero_shot_predict_multilabel(
     rid: usize,
@@ -58,30 +146,186 @@ pub extern "C" fn zero_shot_predict_multilabel(
 ) -> isize {
     exec(|| {
         let input: ZeroShotInput =
-            serde_json::from_slice(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
                 .context("Failed to deserialize zero shot model multilabel config.")?;
 
-        let label_groups = models::with_access(rid, |model| {
-            let model = match model {
-                Model::ZeroShotClassificationModel(m) => m,
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "Expected zero shot classification model at rid '{}'.",
-                        rid
-                    ))
-                }
+        let label_groups = with_zero_shot_model(rid, |model| classify_multilabel(model, &input))?;
+
+        Ok(set_result(
+            codec::encode(&label_groups)
+                .context("Failed to serialize zero shot label groups output.")?,
+        ) as isize)
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZeroShotDistributionInput {
+    #[serde(flatten)]
+    pub input: ZeroShotInput,
+    /// Whether more than one label can be true for an input.
+    #[serde(default)]
+    pub multi_label: bool,
+    /// Labels with a probability below this are left out.
+    pub threshold: Option<f64>,
+    /// The maximum number of labels to return for each input.
+    pub top_k: Option<usize>,
+    /// With `tensor`, the result is a `probabilities` tensor of the shape `[inputs, labels]` in
+    /// the order of the labels, without the threshold and top k.
+    #[serde(default)]
+    pub format: ResultFormat,
+}
+
+#[derive(Serialize)]
+struct ZeroShotTensorMetadata<'a> {
+    labels: &'a [String],
+}
+
+#[derive(Serialize)]
+pub struct JSLabelProbability {
+    pub text: String,
+    pub id: i64,
+    pub probability: f64,
+}
+
+/// Predicts the softmax over the labels of the entailment logits of each input, which is how
+/// `ZeroShotClassificationModel::predict` ranks the labels. rust-bert only returns the best
+/// label, so every label is paired with the first label: the score of the better label of a pair
+/// is the sigmoid of the difference of their entailment logits.
+fn entailment_softmax(
+    model: &ZeroShotClassificationModel,
+    input: &ZeroShotInput,
+) -> Result<Vec<Vec<f64>>, anyhow::Error> {
+    let inputs = &input.inputs.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+
+    // The entailment logit of each label minus the one of the first label
+    let mut logits = vec![vec![0.0; input.labels.len()]; inputs.len()];
+    for (j, label) in input.labels.iter().enumerate().skip(1) {
+        let pair = [input.labels[0].as_str(), label.as_str()];
+        let best = model.predict(
+            inputs,
+            &pair,
+            input.hypothesis_template()?,
+            input.max_length,
+        );
+        for (logits, best) in logits.iter_mut().zip(best) {
+            // Below 1 so that the difference is finite, which only loses a difference that the
+            // softmax can't tell apart from a larger one anyway
+            let score = best.score.min(1.0 - f32::EPSILON as f64);
+            let difference = (score / (1.0 - score)).ln();
+            logits[j] = if best.id == 1 {
+                difference
+            } else {
+                -difference
             };
+        }
+    }
+
+    Ok(logits
+        .into_iter()
+        .map(|logits| {
+            let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let exps = logits.iter().map(|l| (l - max).exp()).collect::<Vec<_>>();
+            let total: f64 = exps.iter().sum();
+            exps.into_iter().map(|e| e / total).collect()
+        })
+        .collect())
+}
+
+/// Predicts the probability of every label for each input, in the order of the labels.
+/// Multi-label probabilities are the independent entailment-vs-contradiction probabilities of
+/// each label and single-label probabilities are the softmax of the entailment logits of the
+/// labels, like `zero_shot_predict` scores its label.
+pub fn label_probabilities(
+    model: &ZeroShotClassificationModel,
+    input: &ZeroShotInput,
+    multi_label: bool,
+) -> Result<Vec<Vec<f64>>, anyhow::Error> {
+    // rust-bert can't predict an empty batch
+    if input.inputs.is_empty() || input.labels.is_empty() {
+        return Ok(input.inputs.iter().map(|_| Vec::new()).collect());
+    }
+
+    if multi_label {
+        Ok(classify_multilabel(model, input)?
+            .into_iter()
+            .map(|labels| labels.iter().map(|l| l.score).collect())
+            .collect())
+    } else {
+        entailment_softmax(model, input)
+    }
+}
+
+/// Predicts the probability of every label for each input, ranked from the most to least probable
+/// label, see `label_probabilities`.
+pub fn classify_distribution(
+    model: &ZeroShotClassificationModel,
+    init: &ZeroShotDistributionInput,
+) -> Result<Vec<Vec<JSLabelProbability>>, anyhow::Error> {
+    let probabilities = label_probabilities(model, &init.input, init.multi_label)?;
+
+    Ok(probabilities
+        .into_iter()
+        .map(|probabilities| {
+            let mut distribution: Vec<JSLabelProbability> = init
+                .input
+                .labels
+                .iter()
+                .zip(probabilities)
+                .enumerate()
+                .filter(|(_, (_, p))| init.threshold.map_or(true, |t| *p >= t))
+                .map(|(id, (text, probability))| JSLabelProbability {
+                    text: text.clone(),
+                    id: id as i64,
+                    probability,
+                })
+                .collect();
+            distribution.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+            if let Some(top_k) = init.top_k {
+                distribution.truncate(top_k);
+            }
+            distribution
+        })
+        .collect())
+}
+
+#[ffi]
+#[no_mangle]
+pub extern "C" fn zero_shot_predict_distribution(
+    rid: usize,
+    buf: *const u8,
+    buf_len: usize,
+) -> isize {
+    exec(|| {
+        let init: ZeroShotDistributionInput =
+            codec::decode(unsafe { std::slice::from_raw_parts(buf, buf_len) })
+                .context("Failed to deserialize zero shot model distribution config.")?;
 
-            let inputs = &input.inputs.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+        if init.format == ResultFormat::Tensor {
+            let probabilities = with_zero_shot_model(rid, |model| {
+                label_probabilities(model, &init.input, init.multi_label)
+            })?;
 
-            let labels = &input.labels.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+            let mut frame = TensorFrame::new();
+            frame.push_f32(
+                "probabilities",
+                vec![probabilities.len() as i64, init.input.labels.len() as i64],
+                &probabilities
+                    .iter()
+                    .flatten()
+                    .map(|&p| p as f32)
+                    .collect::<Vec<_>>(),
+            );
+            return Ok(set_result(frame.into_bytes(ZeroShotTensorMetadata {
+                labels: &init.input.labels,
+            })?) as isize);
+        }
 
-            Ok(model.predict_multilabel(inputs, labels, None, input.max_length))
-        })?;
+        let distributions = with_zero_shot_model(rid, |model| classify_distribution(model, &init))?;
 
         Ok(set_result(
-            serde_json::to_vec(&label_groups)
-                .context("Failed to serialize zero shot label groups output.")?,
+            codec::encode(&distributions)
+                .context("Failed to serialize zero shot distribution output.")?,
         ) as isize)
     })
 }
@@ -0,0 +1,314 @@
+// Generated by `generate_symbols.ts` from the symbol manifest, do not edit.
+import type { TypedDLOpenDynamicLib } from "https://deno.land/x/typedffi@v0.1.2/mod.ts";
+
+/**
+ * The exported functions of the library. Every call is nonblocking so that
+ * inference doesn't block the event loop.
+ */
+export const symbolDefinitions = {
+  aspect_sentiment_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  bertml_abi_version: {
+    parameters: [],
+    result: "u32",
+    nonblocking: true,
+  },
+  bertml_info: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  bertml_symbols: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  conversation_send: {
+    parameters: ["usize", "usize", "usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_conversation: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_conversation_manager: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_conversation_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_encoder_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_fill_mask_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_keyword_extraction_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_ner_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_pos_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_qa_corpus: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_qa_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_sentence_embeddings_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_sentiment_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_summarization_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_text_generation_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_tokenizer: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_translation_model: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_vector_index: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  create_zero_shot_model: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  delete_model: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  delete_model_resource: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  encoder_forward: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  error_len: {
+    parameters: [],
+    result: "usize",
+    nonblocking: true,
+  },
+  fill_error: {
+    parameters: ["buffer", "usize"],
+    result: "void",
+    nonblocking: true,
+  },
+  fill_mask_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  fill_result: {
+    parameters: ["buffer", "usize"],
+    result: "void",
+    nonblocking: true,
+  },
+  get_codec: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  get_model_stats: {
+    parameters: [],
+    result: "isize",
+    nonblocking: true,
+  },
+  keywords_extract: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  ner_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  pos_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  qa_corpus_query: {
+    parameters: ["usize", "usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  qa_query: {
+    parameters: ["usize", "buffer", "usize", "usize", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  qa_query_contexts: {
+    parameters: ["usize", "buffer", "usize", "usize", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  sentence_embeddings_encode: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  sentiment_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  sentiment_predict_document: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  set_codec: {
+    parameters: ["u8"],
+    result: "isize",
+    nonblocking: true,
+  },
+  set_model_memory_budget: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  summarization_summarize: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  text_generation_generate: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  tokenizer_decode: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  tokenizer_encode: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  tokenizer_max_length: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  translation_translate: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_add: {
+    parameters: ["usize", "buffer", "usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_add_texts: {
+    parameters: ["usize", "usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_info: {
+    parameters: ["usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_load: {
+    parameters: ["buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_remove: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_save: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_search: {
+    parameters: ["usize", "buffer", "usize", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  vector_index_search_texts: {
+    parameters: ["usize", "usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  zero_shot_predict: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  zero_shot_predict_distribution: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+  zero_shot_predict_multilabel: {
+    parameters: ["usize", "buffer", "usize"],
+    result: "isize",
+    nonblocking: true,
+  },
+} as const;
+
+/** The typed native functions of `symbolDefinitions`. */
+export type FFISymbols = TypedDLOpenDynamicLib<
+  typeof symbolDefinitions
+>["symbols"];
@@ -0,0 +1,43 @@
+import {
+  decode as decodeMessagePack,
+  encode as encodeMessagePack,
+} from "npm:@msgpack/msgpack@2.8.0";
+import { decode as decodeCbor, encode as encodeCbor } from "npm:cbor-x@1.5.4";
+import { encode } from "./encode.ts";
+import { decode } from "./decode.ts";
+
+/**
+ * The encoding of the structured inputs and results of the native functions.
+ * Raw inputs (e.g. conversation messages and vectors) and tensor frames are
+ * not affected.
+ */
+export type Codec = "json" | "messagePack" | "cbor";
+
+/** The ids of the codecs in `set_codec`. */
+export const codecIds: Record<Codec, number> = {
+  json: 0,
+  messagePack: 1,
+  cbor: 2,
+};
+
+export const serialize = (codec: Codec, value: unknown): Uint8Array => {
+  switch (codec) {
+    case "json":
+      return encode(JSON.stringify(value));
+    case "messagePack":
+      return encodeMessagePack(value);
+    case "cbor":
+      return encodeCbor(value);
+  }
+};
+
+export const deserialize = <T>(codec: Codec, buf: Uint8Array): T => {
+  switch (codec) {
+    case "json":
+      return JSON.parse(decode(buf));
+    case "messagePack":
+      return decodeMessagePack(buf) as T;
+    case "cbor":
+      return decodeCbor(buf);
+  }
+};
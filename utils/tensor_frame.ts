@@ -0,0 +1,52 @@
+import { decode } from "./decode.ts";
+
+/**
+ * How a call that supports binary results encodes its result: `"json"` or a
+ * tensor frame that is viewed as typed arrays without parsing.
+ */
+export type ResultFormat = "json" | "tensor";
+
+/** A tensor of little-endian `f32`s or `i64`s in row-major order. */
+export interface Tensor<T extends Float32Array | BigInt64Array = Float32Array> {
+  shape: number[];
+  data: T;
+}
+
+interface TensorInfo {
+  name: string;
+  dtype: "f32" | "i64";
+  shape: number[];
+  offset: number;
+  byteLength: number;
+}
+
+/**
+ * Reads a tensor frame: the byte length of a JSON header (`u32`), the header,
+ * padding to a multiple of 8 bytes and then the data of the tensors. The
+ * tensors are views into `buf`, which must be aligned to 8 bytes.
+ */
+export const decodeTensorFrame = <M>(
+  buf: Uint8Array,
+): {
+  tensors: Record<string, Tensor<Float32Array | BigInt64Array>>;
+  metadata: M;
+} => {
+  const view = new DataView(buf.buffer, buf.byteOffset, buf.byteLength);
+  const headerLen = view.getUint32(0, true);
+  const header: { tensors: TensorInfo[]; metadata: M } = JSON.parse(
+    decode(buf.subarray(4, 4 + headerLen)),
+  );
+  const dataStart = 4 + headerLen + ((8 - ((4 + headerLen) % 8)) % 8);
+
+  const tensors: Record<string, Tensor<Float32Array | BigInt64Array>> = {};
+  for (const info of header.tensors) {
+    const offset = buf.byteOffset + dataStart + info.offset;
+    tensors[info.name] = {
+      shape: info.shape,
+      data: info.dtype === "i64"
+        ? new BigInt64Array(buf.buffer, offset, info.byteLength / 8)
+        : new Float32Array(buf.buffer, offset, info.byteLength / 4),
+    };
+  }
+  return { tensors, metadata: header.metadata };
+};